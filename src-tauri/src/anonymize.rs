@@ -0,0 +1,82 @@
+use base64::Engine as _;
+use std::collections::HashMap;
+
+/// 表示名・アイコンを仮名に置き換える（同じユーザーには常に同じ仮名を割り当てる）
+#[derive(Default)]
+pub struct Anonymizer {
+    aliases: HashMap<String, String>,
+}
+
+impl Anonymizer {
+    /// ユーザーIDに対応する仮名（初めて見たユーザーには次の仮名を割り当てる）
+    pub fn alias(&mut self, user_id: &str) -> String {
+        let next = self.aliases.len();
+        self.aliases
+            .entry(user_id.to_string())
+            .or_insert_with(|| format!("メンバー{}", alias_suffix(next)))
+            .clone()
+    }
+}
+
+/// 0 → A, 25 → Z, 26 → AA のように英字の連番を返す
+fn alias_suffix(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+/// FNV-1a（起動ごとに変わらないハッシュ）
+fn fnv1a(seed: &str) -> u64 {
+    seed.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// ユーザーIDから左右対称の 5x5 アイコンを生成し、data URL（SVG）で返す
+pub fn identicon(user_id: &str) -> String {
+    let hash = fnv1a(user_id);
+    let hue = (hash >> 40) % 360;
+    let mut cells = String::new();
+    for row in 0..5 {
+        for col in 0..3 {
+            if (hash >> (row * 3 + col)) & 1 == 1 {
+                cells.push_str(&format!(r#"<rect x="{}" y="{}" width="1" height="1"/>"#, col, row));
+                if col < 2 {
+                    cells.push_str(&format!(r#"<rect x="{}" y="{}" width="1" height="1"/>"#, 4 - col, row));
+                }
+            }
+        }
+    }
+    let svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-0.5 -0.5 6 6" shape-rendering="crispEdges"><rect x="-0.5" y="-0.5" width="6" height="6" fill="#f0f0f0"/><g fill="hsl({}, 55%, 50%)">{}</g></svg>"##,
+        hue, cells
+    );
+    format!(
+        "data:image/svg+xml;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(svg)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_are_stable_per_user() {
+        let mut anonymizer = Anonymizer::default();
+        assert_eq!(anonymizer.alias("U1"), "メンバーA");
+        assert_eq!(anonymizer.alias("U2"), "メンバーB");
+        assert_eq!(anonymizer.alias("U1"), "メンバーA");
+        assert_eq!(alias_suffix(25), "Z");
+        assert_eq!(alias_suffix(26), "AA");
+        assert_eq!(alias_suffix(27), "AB");
+        assert_eq!(identicon("U1"), identicon("U1"));
+        assert_ne!(identicon("U1"), identicon("U2"));
+    }
+}
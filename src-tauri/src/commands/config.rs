@@ -25,9 +25,10 @@ pub async fn save_settings(
     slack: State<'_, SlackClientState>,
 ) -> Result<ConfigSaveResult, String> {
     // SlackClientの設定も更新
-    slack.update_config(config.clone()).await;
+    slack.update_config(config).await;
 
-    match storage.save_config(&config) {
+    // フロントエンドが持っていない項目（ウォッチ中のスレッドやルールなど）も含めて保存する
+    match storage.save_config(&slack.get_config().await) {
         Ok(()) => Ok(ConfigSaveResult {
             success: true,
             error: None,
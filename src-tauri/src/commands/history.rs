@@ -0,0 +1,90 @@
+use crate::history::{HistoryEntry, HistoryQuery, HistorySettings, HistoryState};
+use crate::history_export::{self, HistoryExportRequest};
+use crate::slack_client::{ChannelActionResult, SlackClientState};
+use crate::storage::StorageState;
+use tauri::State;
+
+#[tauri::command]
+pub async fn search_history(
+    query: HistoryQuery,
+    history: State<'_, HistoryState>,
+) -> Result<Vec<HistoryEntry>, String> {
+    history.search(&query)
+}
+
+#[tauri::command]
+pub async fn get_history_settings(history: State<'_, HistoryState>) -> Result<HistorySettings, String> {
+    history.settings()
+}
+
+#[tauri::command]
+pub async fn set_history_settings(
+    settings: HistorySettings,
+    history: State<'_, HistoryState>,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("set_history_settings コマンド呼び出し: {:?}", settings);
+    if let Err(e) = history.set_settings(settings.clone()) {
+        return Ok(ChannelActionResult {
+            success: false,
+            error: Some(e),
+            message: None,
+        });
+    }
+    slack.set_history_settings(settings, &storage).await;
+    Ok(ChannelActionResult {
+        success: true,
+        error: None,
+        message: Some("履歴の保存設定を更新しました".to_string()),
+    })
+}
+
+/// 指定期間・チャンネルの履歴をファイルに書き出す
+#[tauri::command]
+pub async fn export_history(
+    request: HistoryExportRequest,
+    history: State<'_, HistoryState>,
+    slack: State<'_, SlackClientState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("export_history コマンド呼び出し: {:?} -> {}", request.format, request.path);
+    // 表示したメッセージだけを、画面と同じくマスクして書き出す
+    let query = HistoryQuery {
+        channels: request.channels.clone(),
+        from: request.from.clone(),
+        to: request.to.clone(),
+        include_hidden: false,
+        ..Default::default()
+    };
+    let mut entries = match history.export_entries(&query) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return Ok(ChannelActionResult {
+                success: false,
+                error: Some(e),
+                message: None,
+            })
+        }
+    };
+    slack.mask_history_entries(&mut entries).await;
+    let result = history_export::render(&entries, request.format)
+        .map(|content| (content, entries.len()))
+        .and_then(|(content, count)| {
+            std::fs::write(&request.path, content)
+                .map(|_| count)
+                .map_err(|e| format!("書き出しエラー: {}", e))
+        });
+
+    Ok(match result {
+        Ok(count) => ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some(format!("{}件のメッセージを書き出しました", count)),
+        },
+        Err(e) => ChannelActionResult {
+            success: false,
+            error: Some(e),
+            message: None,
+        },
+    })
+}
@@ -1,2 +1,4 @@
 pub mod config;
+pub mod history;
+pub mod queue;
 pub mod slack;
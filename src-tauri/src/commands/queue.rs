@@ -0,0 +1,80 @@
+use crate::message_queue::{MessageQueue, MessageQueueState};
+use crate::slack_client::SlackMessage;
+use tauri::{AppHandle, State};
+
+#[tauri::command]
+pub async fn queue_get_state(queue: State<'_, MessageQueueState>) -> Result<MessageQueue, String> {
+    Ok(queue.snapshot().await)
+}
+
+#[tauri::command]
+pub async fn queue_add_message(
+    message: SlackMessage,
+    queue: State<'_, MessageQueueState>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    Ok(queue.push(message, &app_handle).await)
+}
+
+#[tauri::command]
+pub async fn queue_add_text(
+    text: String,
+    queue: State<'_, MessageQueueState>,
+    app_handle: AppHandle,
+) -> Result<u64, String> {
+    if text.trim().is_empty() {
+        return Err("テキストが空です".to_string());
+    }
+    Ok(queue.push(SlackMessage::plain(text), &app_handle).await)
+}
+
+#[tauri::command]
+pub async fn queue_remove(
+    id: u64,
+    queue: State<'_, MessageQueueState>,
+    app_handle: AppHandle,
+) -> Result<bool, String> {
+    Ok(queue.remove(id, &app_handle).await)
+}
+
+#[tauri::command]
+pub async fn queue_play(queue: State<'_, MessageQueueState>, app_handle: AppHandle) -> Result<bool, String> {
+    log::info!("queue_play コマンド呼び出し");
+    Ok(queue.play(&app_handle).await)
+}
+
+#[tauri::command]
+pub async fn queue_select(
+    index: usize,
+    queue: State<'_, MessageQueueState>,
+    app_handle: AppHandle,
+) -> Result<bool, String> {
+    Ok(queue.select(index, &app_handle).await)
+}
+
+#[tauri::command]
+pub async fn queue_pause(queue: State<'_, MessageQueueState>, app_handle: AppHandle) -> Result<(), String> {
+    log::info!("queue_pause コマンド呼び出し");
+    queue.pause(&app_handle).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn queue_resume(queue: State<'_, MessageQueueState>, app_handle: AppHandle) -> Result<bool, String> {
+    log::info!("queue_resume コマンド呼び出し");
+    Ok(queue.resume(&app_handle).await)
+}
+
+#[tauri::command]
+pub async fn queue_stop(queue: State<'_, MessageQueueState>, app_handle: AppHandle) -> Result<(), String> {
+    log::info!("queue_stop コマンド呼び出し");
+    queue.stop(&app_handle).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn queue_clear(queue: State<'_, MessageQueueState>, app_handle: AppHandle) -> Result<(), String> {
+    log::info!("queue_clear コマンド呼び出し");
+    queue.clear(&app_handle).await;
+    Ok(())
+}
@@ -1,11 +1,21 @@
+use crate::event_recorder::RecorderStatus;
+use crate::filters::FilterRule;
+use crate::highlight::HighlightRule;
+use crate::ng_words::{NgWordEntry, NgWordFormat};
+use crate::pause::{PauseState, ResumeMode};
+use crate::redaction::RedactionSettings;
+use crate::replay::ReplayState;
+use crate::schedule::{ScheduleSettings, ScheduleState};
 use crate::slack_client::{
-    CacheStatus, ChannelActionResult, ChannelListResult, EmojiListResult,
-    SlackChannel, SlackClientState, SlackConfig, SlackConnectionResult,
-    WatchedChannelsResult,
+    CacheStatus, ChannelActionResult, ChannelListResult, ChannelRule, ChannelRuleApplyResult,
+    ChannelSettings, ChannelValidationResult, EmojiListResult, FilterStats, FilterTestResult,
+    SlackChannel, SlackClientState, SlackConfig, SlackConnectionResult, ThreadResult,
+    WatchedChannelsResult, WatchedThread,
 };
 use crate::storage::StorageState;
+use crate::throttle::ThrottleMetrics;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 // --- 接続管理 ---
 
@@ -16,7 +26,34 @@ pub async fn slack_connect(
     app_handle: AppHandle,
 ) -> Result<SlackConnectionResult, String> {
     slack.update_config(config).await;
-    Ok(slack.connect(app_handle).await)
+    let result = slack.connect(app_handle.clone()).await;
+
+    // 監視チャンネルの状態確認と、チャンネルルールに一致するチャンネルの自動追加を接続時に実行
+    // （API 呼び出しが多いためバックグラウンドで実行）
+    if result.success {
+        tokio::spawn(async move {
+            let slack = app_handle.state::<SlackClientState>();
+            let storage = app_handle.state::<StorageState>();
+
+            let validation = slack.validate_watched_channels().await;
+            if validation.success {
+                let _ = app_handle.emit("channel-health", &validation.channels);
+            } else if let Some(e) = validation.error {
+                log::warn!("監視チャンネルの状態確認失敗: {}", e);
+            }
+
+            let rule_result = slack.apply_channel_rules(&storage).await;
+            if !rule_result.added.is_empty() {
+                log::info!("チャンネルルールにより{}チャンネルを追加", rule_result.added.len());
+                let channel_name = slack.get_current_channel_name().await;
+                let _ = app_handle.emit("channel-updated", &channel_name);
+            } else if let Some(e) = rule_result.error {
+                log::warn!("チャンネルルール適用失敗: {}", e);
+            }
+        });
+    }
+
+    Ok(result)
 }
 
 #[tauri::command]
@@ -51,12 +88,15 @@ pub async fn slack_get_channels(
 #[tauri::command]
 pub async fn slack_add_channel(
     channel_id: String,
+    auto_join: Option<bool>,
     slack: State<'_, SlackClientState>,
     storage: State<'_, StorageState>,
     app_handle: AppHandle,
 ) -> Result<ChannelActionResult, String> {
-    log::info!("slack_add_channel コマンド呼び出し: {}", channel_id);
-    let result = slack.add_watch_channel(&channel_id, &storage).await;
+    log::info!("slack_add_channel コマンド呼び出し: {} auto_join={:?}", channel_id, auto_join);
+    let result = slack
+        .add_watch_channel(&channel_id, auto_join.unwrap_or(false), &storage)
+        .await;
     log::info!("slack_add_channel 結果: {:?}", result);
 
     // 表示ウィンドウにチャンネル更新を通知
@@ -109,8 +149,424 @@ pub async fn get_current_channel_name(
     Ok(slack.get_current_channel_name().await)
 }
 
+#[tauri::command]
+pub async fn slack_get_channel_settings(
+    channel_id: String,
+    slack: State<'_, SlackClientState>,
+) -> Result<ChannelSettings, String> {
+    Ok(slack.get_channel_settings(&channel_id).await)
+}
+
+#[tauri::command]
+pub async fn slack_set_channel_settings(
+    channel_id: String,
+    settings: ChannelSettings,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_set_channel_settings コマンド呼び出し: {} {:?}", channel_id, settings);
+    Ok(slack.set_channel_settings(&channel_id, settings, &storage).await)
+}
+
+// --- チャンネルルール ---
+
+#[tauri::command]
+pub async fn slack_add_channel_rule(
+    rule: ChannelRule,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_add_channel_rule コマンド呼び出し: {:?}", rule);
+    Ok(slack.add_channel_rule(rule, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_remove_channel_rule(
+    pattern: String,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_remove_channel_rule コマンド呼び出し: {}", pattern);
+    Ok(slack.remove_channel_rule(&pattern, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_get_channel_rules(
+    slack: State<'_, SlackClientState>,
+) -> Result<Vec<ChannelRule>, String> {
+    Ok(slack.get_channel_rules().await)
+}
+
+#[tauri::command]
+pub async fn slack_apply_channel_rules(
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+    app_handle: AppHandle,
+) -> Result<ChannelRuleApplyResult, String> {
+    let result = slack.apply_channel_rules(&storage).await;
+    if !result.added.is_empty() {
+        let channel_name = slack.get_current_channel_name().await;
+        let _ = app_handle.emit("channel-updated", &channel_name);
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn slack_validate_channels(
+    slack: State<'_, SlackClientState>,
+    app_handle: AppHandle,
+) -> Result<ChannelValidationResult, String> {
+    let result = slack.validate_watched_channels().await;
+    if result.success {
+        let _ = app_handle.emit("channel-health", &result.channels);
+    }
+    Ok(result)
+}
+
+// --- メッセージフィルタ ---
+
+#[tauri::command]
+pub async fn slack_add_filter_rule(
+    rule: FilterRule,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_add_filter_rule コマンド呼び出し: {:?}", rule);
+    Ok(slack.add_filter_rule(rule, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_remove_filter_rule(
+    id: String,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_remove_filter_rule コマンド呼び出し: {}", id);
+    Ok(slack.remove_filter_rule(&id, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_get_filter_rules(
+    slack: State<'_, SlackClientState>,
+) -> Result<Vec<FilterRule>, String> {
+    Ok(slack.get_filter_rules().await)
+}
+
+#[tauri::command]
+pub async fn slack_test_filter_rule(
+    rule: FilterRule,
+    text: String,
+    user_id: Option<String>,
+    slack: State<'_, SlackClientState>,
+) -> Result<FilterTestResult, String> {
+    Ok(slack.test_filter_rule(&rule, &text, user_id.as_deref()))
+}
+
+#[tauri::command]
+pub async fn slack_get_filter_stats(
+    slack: State<'_, SlackClientState>,
+) -> Result<FilterStats, String> {
+    Ok(slack.get_filter_stats().await)
+}
+
+// --- マスク ---
+
+#[tauri::command]
+pub async fn slack_get_redaction_settings(
+    slack: State<'_, SlackClientState>,
+) -> Result<RedactionSettings, String> {
+    Ok(slack.get_redaction_settings().await)
+}
+
+#[tauri::command]
+pub async fn slack_set_redaction_settings(
+    settings: RedactionSettings,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    Ok(slack.set_redaction_settings(settings, &storage).await)
+}
+
+// --- ハイライト ---
+
+#[tauri::command]
+pub async fn slack_add_highlight_rule(
+    rule: HighlightRule,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_add_highlight_rule コマンド呼び出し: {:?}", rule);
+    Ok(slack.add_highlight_rule(rule, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_remove_highlight_rule(
+    id: String,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_remove_highlight_rule コマンド呼び出し: {}", id);
+    Ok(slack.remove_highlight_rule(&id, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_get_highlight_rules(
+    slack: State<'_, SlackClientState>,
+) -> Result<Vec<HighlightRule>, String> {
+    Ok(slack.get_highlight_rules().await)
+}
+
+// --- 一時停止 ---
+
+#[tauri::command]
+pub async fn slack_get_pause_state(
+    slack: State<'_, SlackClientState>,
+) -> Result<PauseState, String> {
+    Ok(slack.get_pause_state().await)
+}
+
+#[tauri::command]
+pub async fn slack_pause_stream(
+    slack: State<'_, SlackClientState>,
+    app_handle: AppHandle,
+) -> Result<PauseState, String> {
+    log::info!("slack_pause_stream コマンド呼び出し");
+    Ok(slack.pause_stream(&app_handle).await)
+}
+
+#[tauri::command]
+pub async fn slack_resume_stream(
+    mode: ResumeMode,
+    slack: State<'_, SlackClientState>,
+    app_handle: AppHandle,
+) -> Result<PauseState, String> {
+    log::info!("slack_resume_stream コマンド呼び出し: {:?}", mode);
+    Ok(slack.resume_stream(mode, &app_handle).await)
+}
+
+// --- 受信イベントの記録 ---
+
+#[tauri::command]
+pub async fn slack_get_event_recording(
+    slack: State<'_, SlackClientState>,
+) -> Result<RecorderStatus, String> {
+    slack.get_event_recording().await
+}
+
+#[tauri::command]
+pub async fn slack_set_event_recording(
+    enabled: bool,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<RecorderStatus, String> {
+    log::info!("slack_set_event_recording コマンド呼び出し: {}", enabled);
+    slack.set_event_recording(enabled, storage.event_log_dir()).await
+}
+
+// --- リプレイ ---
+
+#[tauri::command]
+pub async fn replay_get_state(
+    slack: State<'_, SlackClientState>,
+) -> Result<ReplayState, String> {
+    Ok(slack.get_replay_state().await)
+}
+
+#[tauri::command]
+pub async fn replay_start(
+    path: String,
+    speed: f64,
+    slack: State<'_, SlackClientState>,
+    app_handle: AppHandle,
+) -> Result<ReplayState, String> {
+    log::info!("replay_start コマンド呼び出し: {} ({}倍速)", path, speed);
+    slack.start_replay(&path, speed, &app_handle).await
+}
+
+#[tauri::command]
+pub async fn replay_stop(
+    slack: State<'_, SlackClientState>,
+    app_handle: AppHandle,
+) -> Result<ReplayState, String> {
+    log::info!("replay_stop コマンド呼び出し");
+    Ok(slack.stop_replay(&app_handle).await)
+}
+
+// --- スケジュール ---
+
+#[tauri::command]
+pub async fn slack_get_schedule(
+    slack: State<'_, SlackClientState>,
+) -> Result<ScheduleSettings, String> {
+    Ok(slack.get_schedule().await)
+}
+
+#[tauri::command]
+pub async fn slack_set_schedule(
+    settings: ScheduleSettings,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+    app_handle: AppHandle,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_set_schedule コマンド呼び出し");
+    Ok(slack.set_schedule(settings, &storage, &app_handle).await)
+}
+
+#[tauri::command]
+pub async fn slack_get_schedule_state(
+    slack: State<'_, SlackClientState>,
+) -> Result<ScheduleState, String> {
+    Ok(slack.get_schedule_state().await)
+}
+
+// --- 流量制限 ---
+
+#[tauri::command]
+pub async fn slack_get_throttle_metrics(
+    slack: State<'_, SlackClientState>,
+) -> Result<ThrottleMetrics, String> {
+    Ok(slack.get_throttle_metrics().await)
+}
+
+// --- 匿名表示 ---
+
+#[tauri::command]
+pub async fn slack_get_anonymous_mode(
+    slack: State<'_, SlackClientState>,
+) -> Result<bool, String> {
+    Ok(slack.get_anonymous_mode().await)
+}
+
+#[tauri::command]
+pub async fn slack_set_anonymous_mode(
+    enabled: bool,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_set_anonymous_mode コマンド呼び出し: {}", enabled);
+    Ok(slack.set_anonymous_mode(enabled, &storage).await)
+}
+
+// --- NGワード ---
+
+#[tauri::command]
+pub async fn slack_get_ng_words(
+    slack: State<'_, SlackClientState>,
+) -> Result<Vec<NgWordEntry>, String> {
+    Ok(slack.get_ng_words().await)
+}
+
+#[tauri::command]
+pub async fn slack_add_ng_word(
+    word: String,
+    hide_message: bool,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_add_ng_word コマンド呼び出し: {}", word);
+    Ok(slack.add_ng_word(&word, hide_message, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_remove_ng_word(
+    word: String,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_remove_ng_word コマンド呼び出し: {}", word);
+    Ok(slack.remove_ng_word(&word, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_import_ng_words(
+    content: String,
+    format: NgWordFormat,
+    replace: bool,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_import_ng_words コマンド呼び出し: {:?} replace={}", format, replace);
+    Ok(slack.import_ng_words(&content, format, replace, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_export_ng_words(
+    format: NgWordFormat,
+    slack: State<'_, SlackClientState>,
+) -> Result<String, String> {
+    Ok(slack.export_ng_words(format).await)
+}
+
+// --- スレッド ---
+
+#[tauri::command]
+pub async fn slack_get_thread(
+    channel_id: String,
+    thread_ts: String,
+    slack: State<'_, SlackClientState>,
+) -> Result<ThreadResult, String> {
+    log::info!("slack_get_thread コマンド呼び出し: {} {}", channel_id, thread_ts);
+    Ok(slack.get_thread(&channel_id, &thread_ts).await)
+}
+
+#[tauri::command]
+pub async fn slack_watch_thread(
+    channel_id: String,
+    thread_ts: String,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_watch_thread コマンド呼び出し: {} {}", channel_id, thread_ts);
+    Ok(slack.add_watch_thread(&channel_id, &thread_ts, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_unwatch_thread(
+    channel_id: String,
+    thread_ts: String,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_unwatch_thread コマンド呼び出し: {} {}", channel_id, thread_ts);
+    Ok(slack.remove_watch_thread(&channel_id, &thread_ts, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_get_watched_threads(
+    slack: State<'_, SlackClientState>,
+) -> Result<Vec<WatchedThread>, String> {
+    Ok(slack.get_watched_threads().await)
+}
+
 // --- ユーザー管理 ---
 
+#[tauri::command]
+pub async fn slack_follow_user(
+    user_id: String,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_follow_user コマンド呼び出し: {}", user_id);
+    Ok(slack.follow_user(&user_id, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_unfollow_user(
+    user_id: String,
+    slack: State<'_, SlackClientState>,
+    storage: State<'_, StorageState>,
+) -> Result<ChannelActionResult, String> {
+    log::info!("slack_unfollow_user コマンド呼び出し: {}", user_id);
+    Ok(slack.unfollow_user(&user_id, &storage).await)
+}
+
+#[tauri::command]
+pub async fn slack_get_followed_users(
+    slack: State<'_, SlackClientState>,
+) -> Result<Vec<String>, String> {
+    Ok(slack.get_followed_users().await)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UsersReloadResult {
     pub success: bool,
@@ -0,0 +1,183 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// 一致したメッセージを表示しない
+    #[default]
+    Exclude,
+    /// 一致したメッセージだけを表示する
+    Include,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterMatchKind {
+    /// 大文字小文字を区別しない部分一致
+    #[default]
+    Keyword,
+    Regex,
+    /// 発言者のユーザーID
+    User,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterRule {
+    #[serde(default)]
+    pub id: String,
+    /// 対象チャンネルID（None で全チャンネル）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default)]
+    pub action: FilterAction,
+    #[serde(default)]
+    pub kind: FilterMatchKind,
+    pub pattern: String,
+}
+
+enum Matcher {
+    Keyword(String),
+    Regex(Regex),
+    User(String),
+}
+
+impl Matcher {
+    fn is_match(&self, text: &str, user_id: Option<&str>) -> bool {
+        match self {
+            Matcher::Keyword(keyword) => text.to_lowercase().contains(keyword),
+            Matcher::Regex(re) => re.is_match(text),
+            Matcher::User(id) => user_id == Some(id.as_str()),
+        }
+    }
+}
+
+impl FilterRule {
+    fn applies_to(&self, channel: &str) -> bool {
+        self.channel.as_deref().is_none_or(|c| c == channel)
+    }
+
+    fn matcher(&self) -> Result<Matcher, String> {
+        match self.kind {
+            FilterMatchKind::Keyword => Ok(Matcher::Keyword(self.pattern.to_lowercase())),
+            FilterMatchKind::Regex => Regex::new(&self.pattern)
+                .map(Matcher::Regex)
+                .map_err(|e| format!("正規表現が不正です: {}", e)),
+            FilterMatchKind::User => Ok(Matcher::User(self.pattern.trim().to_string())),
+        }
+    }
+
+    /// ルールとして有効か確認する（空パターン・不正な正規表現を拒否）
+    pub fn validate(&self) -> Result<(), String> {
+        if self.pattern.trim().is_empty() {
+            return Err("パターンを入力してください".to_string());
+        }
+        self.matcher().map(|_| ())
+    }
+
+    /// テキスト（メンション解決済みの表示テキスト）と発言者に一致するか
+    pub fn matches(&self, text: &str, user_id: Option<&str>) -> Result<bool, String> {
+        Ok(self.matcher()?.is_match(text, user_id))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterDecision {
+    Pass,
+    /// 除外ルールに一致（ルールID）
+    Excluded(String),
+    /// 絞り込みルールがあるがどれにも一致しない
+    NotIncluded,
+}
+
+/// 照合用の正規表現をコンパイル済みのフィルタルール（ルールが変わったときだけ作り直す）
+#[derive(Default)]
+pub struct FilterSet {
+    rules: Vec<(FilterRule, Matcher)>,
+}
+
+impl FilterSet {
+    pub fn new(rules: &[FilterRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match rule.matcher() {
+                Ok(matcher) => Some((rule.clone(), matcher)),
+                Err(e) => {
+                    log::warn!("フィルタルール {} をスキップ: {}", rule.id, e);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// チャンネルに適用されるルールでメッセージを判定する。
+    /// 除外ルールが優先され、絞り込みルールがある場合はいずれかに一致したものだけを通す。
+    pub fn evaluate(&self, channel: &str, user_id: Option<&str>, text: &str) -> FilterDecision {
+        let mut has_include = false;
+        let mut included = false;
+
+        for (rule, matcher) in self.rules.iter().filter(|(r, _)| r.applies_to(channel)) {
+            let matched = matcher.is_match(text, user_id);
+            match rule.action {
+                FilterAction::Exclude if matched => return FilterDecision::Excluded(rule.id.clone()),
+                FilterAction::Exclude => {}
+                FilterAction::Include => {
+                    has_include = true;
+                    included |= matched;
+                }
+            }
+        }
+
+        if has_include && !included {
+            FilterDecision::NotIncluded
+        } else {
+            FilterDecision::Pass
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(channel: Option<&str>, action: FilterAction, kind: FilterMatchKind, pattern: &str) -> FilterRule {
+        FilterRule {
+            id: pattern.to_string(),
+            channel: channel.map(str::to_string),
+            action,
+            kind,
+            pattern: pattern.to_string(),
+        }
+    }
+
+    #[test]
+    fn exclude_rules_win_over_include() {
+        let rules = FilterSet::new(&[
+            rule(None, FilterAction::Exclude, FilterMatchKind::Regex, r"^\[auto\]"),
+            rule(None, FilterAction::Exclude, FilterMatchKind::User, "U_BOT"),
+            rule(Some("C1"), FilterAction::Include, FilterMatchKind::Keyword, "Deploy"),
+        ]);
+
+        assert_eq!(
+            rules.evaluate("C1", Some("U1"), "[auto] deploy finished"),
+            FilterDecision::Excluded(r"^\[auto\]".to_string())
+        );
+        assert_eq!(
+            rules.evaluate("C2", Some("U_BOT"), "hello"),
+            FilterDecision::Excluded("U_BOT".to_string())
+        );
+        assert_eq!(rules.evaluate("C1", Some("U1"), "DEPLOY started"), FilterDecision::Pass);
+        assert_eq!(rules.evaluate("C1", Some("U1"), "lunch?"), FilterDecision::NotIncluded);
+        // 絞り込みルールは対象チャンネルにだけ適用される
+        assert_eq!(rules.evaluate("C2", Some("U1"), "lunch?"), FilterDecision::Pass);
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        let bad = rule(None, FilterAction::Exclude, FilterMatchKind::Regex, "(unclosed");
+        assert!(bad.validate().is_err());
+        assert_eq!(FilterSet::new(&[bad]).evaluate("C1", None, "(unclosed"), FilterDecision::Pass);
+    }
+}
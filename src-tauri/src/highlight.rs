@@ -0,0 +1,177 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightMatchKind {
+    /// 大文字小文字を区別しない部分一致
+    #[default]
+    Keyword,
+    Regex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightRule {
+    #[serde(default)]
+    pub id: String,
+    /// 対象チャンネルID（None で全チャンネル）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default)]
+    pub kind: HighlightMatchKind,
+    pub pattern: String,
+    /// 表示色（CSS の色指定）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// 範囲が重なった場合は大きい方を優先
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// メッセージ本文中の強調範囲（UTF-16 単位のオフセットで、JavaScript の文字列にそのまま使える）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Highlight {
+    pub rule_id: String,
+    pub start: usize,
+    pub end: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl HighlightRule {
+    pub fn to_regex(&self) -> Result<Regex, String> {
+        if self.pattern.trim().is_empty() {
+            return Err("パターンを入力してください".to_string());
+        }
+        let source = match self.kind {
+            HighlightMatchKind::Keyword => format!("(?i){}", regex::escape(&self.pattern)),
+            HighlightMatchKind::Regex => self.pattern.clone(),
+        };
+        Regex::new(&source).map_err(|e| format!("正規表現が不正です: {}", e))
+    }
+
+    fn applies_to(&self, channel: &str) -> bool {
+        self.channel.as_deref().is_none_or(|c| c == channel)
+    }
+}
+
+/// 強調すると表示が壊れる箇所（HTMLタグ・文字参照・絵文字コード）
+fn protected_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<[^>]*>|&[a-zA-Z0-9#]+;|:[a-z0-9_+'\-]+:").unwrap())
+}
+
+/// 照合用の正規表現をコンパイル済みのハイライトルール（ルールが変わったときだけ作り直す）
+#[derive(Default)]
+pub struct HighlightSet {
+    rules: Vec<(HighlightRule, Regex)>,
+}
+
+impl HighlightSet {
+    pub fn new(rules: &[HighlightRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match rule.to_regex() {
+                Ok(re) => Some((rule.clone(), re)),
+                Err(e) => {
+                    log::warn!("ハイライトルール {} をスキップ: {}", rule.id, e);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// 表示用テキスト（メンション解決済み）からルールに一致する範囲を求める。
+    /// 重なった範囲は優先度の高いルールを残し、開始位置順に返す。
+    pub fn find(&self, channel: &str, text: &str) -> Vec<Highlight> {
+        find_highlights(&self.rules, channel, text)
+    }
+}
+
+fn find_highlights(rules: &[(HighlightRule, Regex)], channel: &str, text: &str) -> Vec<Highlight> {
+    let protected: Vec<(usize, usize)> = protected_regex()
+        .find_iter(text)
+        .map(|m| (m.start(), m.end()))
+        .collect();
+
+    let mut candidates: Vec<(&HighlightRule, usize, usize)> = Vec::new();
+    for (rule, re) in rules.iter().filter(|(r, _)| r.applies_to(channel)) {
+        for m in re.find_iter(text) {
+            let overlaps_protected = protected.iter().any(|&(s, e)| m.start() < e && s < m.end());
+            if !m.is_empty() && !overlaps_protected {
+                candidates.push((rule, m.start(), m.end()));
+            }
+        }
+    }
+
+    // 優先度の高い順（同じなら先に現れた順）に採用し、重なるものは捨てる
+    candidates.sort_by(|a, b| b.0.priority.cmp(&a.0.priority).then(a.1.cmp(&b.1)));
+    let mut accepted: Vec<(&HighlightRule, usize, usize)> = Vec::new();
+    for candidate in candidates {
+        if !accepted.iter().any(|&(_, s, e)| candidate.1 < e && s < candidate.2) {
+            accepted.push(candidate);
+        }
+    }
+    accepted.sort_by_key(|&(_, start, _)| start);
+
+    accepted
+        .into_iter()
+        .map(|(rule, start, end)| Highlight {
+            rule_id: rule.id.clone(),
+            start: text[..start].encode_utf16().count(),
+            end: text[..end].encode_utf16().count(),
+            color: rule.color.clone(),
+            priority: rule.priority,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str, kind: HighlightMatchKind, pattern: &str, priority: i32) -> HighlightRule {
+        HighlightRule {
+            id: id.to_string(),
+            channel: None,
+            kind,
+            pattern: pattern.to_string(),
+            color: None,
+            priority,
+        }
+    }
+
+    #[test]
+    fn ranges_are_utf16_and_skip_markup() {
+        let rules = HighlightSet::new(&[
+            rule("incident", HighlightMatchKind::Keyword, "障害", 0),
+            rule("slack", HighlightMatchKind::Keyword, "slack", 0),
+        ]);
+        let text = r#"<span class="slack-mention">@山田</span> 😀 障害です :slack:"#;
+        let highlights = rules.find("C1", text);
+
+        assert_eq!(highlights.len(), 1);
+        let h = &highlights[0];
+        assert_eq!(h.rule_id, "incident");
+        let utf16: Vec<u16> = text.encode_utf16().collect();
+        assert_eq!(String::from_utf16(&utf16[h.start..h.end]).unwrap(), "障害");
+    }
+
+    #[test]
+    fn higher_priority_wins_on_overlap() {
+        let rules = HighlightSet::new(&[
+            rule("word", HighlightMatchKind::Keyword, "deploy", 0),
+            rule("phrase", HighlightMatchKind::Regex, r"deploy failed", 10),
+        ]);
+        let highlights = rules.find("C1", "deploy failed, deploy again");
+
+        let ids: Vec<&str> = highlights.iter().map(|h| h.rule_id.as_str()).collect();
+        assert_eq!(ids, vec!["phrase", "word"]);
+    }
+}
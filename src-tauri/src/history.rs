@@ -0,0 +1,543 @@
+use chrono::{Local, NaiveDate, TimeZone};
+use regex::{Captures, Regex};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use crate::slack_client::{ReactionData, SlackMessage};
+
+/// 何件記録するごとに保持期間を過ぎた履歴を削除するか
+const PRUNE_EVERY: u32 = 200;
+
+/// 検索結果の既定の件数
+const DEFAULT_SEARCH_LIMIT: u32 = 100;
+
+/// 履歴の保存設定
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistorySettings {
+    pub enabled: bool,
+    /// 保持する日数（0 なら無期限）
+    pub retention_days: u32,
+    /// 保持する最大件数（0 なら無制限）
+    pub max_messages: u32,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 30,
+            max_messages: 100_000,
+        }
+    }
+}
+
+/// 履歴の1件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub channel: Option<String>,
+    pub channel_name: Option<String>,
+    pub ts: Option<String>,
+    pub thread_ts: Option<String>,
+    pub user_id: Option<String>,
+    pub user: String,
+    pub text: String,
+    pub reactions: Vec<ReactionData>,
+    pub is_system: bool,
+    /// 表示しなかった理由（filter / ng_word / schedule / throttled）
+    pub hidden_reason: Option<String>,
+    /// 表示時にメールアドレス・トークン等をマスクした
+    pub redacted: bool,
+    /// 表示時にNGワードを伏せ字にした
+    pub masked: bool,
+    /// 投稿時刻（Unix 秒。ts がないメッセージは受信時刻）
+    pub posted_at: i64,
+}
+
+/// 記録する1件。本文はメンションを実名で解決したプレーンテキストで、表示用の加工は含めない
+#[derive(Debug, Clone, Default)]
+pub struct HistoryRecord {
+    pub channel: Option<String>,
+    pub channel_name: Option<String>,
+    pub ts: Option<String>,
+    pub thread_ts: Option<String>,
+    pub user_id: Option<String>,
+    pub user: String,
+    pub text: String,
+    pub is_system: bool,
+    pub hidden_reason: Option<String>,
+    pub redacted: bool,
+    pub masked: bool,
+}
+
+impl HistoryRecord {
+    /// 参加・退出などのシステムメッセージ
+    pub fn system(message: &SlackMessage) -> Self {
+        Self {
+            channel: message.channel.clone(),
+            channel_name: message.channel_name.clone(),
+            ts: message.timestamp.clone(),
+            thread_ts: message.thread_ts.clone(),
+            user_id: message.user_id.clone(),
+            user: message.user.clone(),
+            text: plain_text(&message.text),
+            is_system: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// 検索条件（すべて省略可能）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryQuery {
+    /// 空白区切りの語をすべて含むメッセージを探す
+    pub query: Option<String>,
+    pub channels: Vec<String>,
+    pub user_ids: Vec<String>,
+    /// "YYYY-MM-DD"（ローカル日付、両端を含む）
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub limit: Option<u32>,
+    /// フィルタ等で表示しなかったメッセージも含める
+    pub include_hidden: bool,
+}
+
+/// メッセージ履歴（SQLite）
+pub struct HistoryStore {
+    conn: Connection,
+    settings: HistorySettings,
+    since_prune: u32,
+}
+
+fn parse_day(value: &str, end_of_day: bool) -> Result<i64, String> {
+    let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("日付は YYYY-MM-DD で指定してください: {}", value))?;
+    let date = if end_of_day { date.succ_opt().unwrap_or(date) } else { date };
+    let start = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Ok(Local
+        .from_local_datetime(&start)
+        .earliest()
+        .map(|t| t.timestamp())
+        .unwrap_or_else(|| start.and_utc().timestamp()))
+}
+
+/// Slack の ts（"1700000000.123456"）を Unix 秒にする
+fn ts_seconds(ts: &str) -> Option<i64> {
+    ts.split('.').next()?.parse().ok()
+}
+
+fn markup_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"</?[a-zA-Z][a-zA-Z0-9]*(?:\s[^>]*)?>|&(?:amp|lt|gt|quot|#39);").unwrap())
+}
+
+/// 表示用テキスト（メンションの `<span>` や文字参照を含む）をプレーンテキストにする
+pub fn plain_text(text: &str) -> String {
+    markup_regex()
+        .replace_all(text, |caps: &Captures| match &caps[0] {
+            "&amp;" => "&",
+            "&lt;" => "<",
+            "&gt;" => ">",
+            "&quot;" => "\"",
+            "&#39;" => "'",
+            _ => "",
+        })
+        .into_owned()
+}
+
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("履歴データベースエラー: {}", e)
+}
+
+impl HistoryStore {
+    pub fn open(path: &Path, settings: HistorySettings) -> Result<Self, String> {
+        Self::init(Connection::open(path).map_err(db_error)?, settings)
+    }
+
+    fn init(conn: Connection, settings: HistorySettings) -> Result<Self, String> {
+        // trigram トークナイザで日本語も部分一致で検索できる（2文字以下の語は LIKE で探す）
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS messages (
+                id INTEGER PRIMARY KEY,
+                channel TEXT,
+                channel_name TEXT,
+                ts TEXT,
+                thread_ts TEXT,
+                user_id TEXT,
+                user TEXT NOT NULL,
+                text TEXT NOT NULL,
+                reactions TEXT NOT NULL DEFAULT '[]',
+                is_system INTEGER NOT NULL DEFAULT 0,
+                hidden_reason TEXT,
+                redacted INTEGER NOT NULL DEFAULT 0,
+                masked INTEGER NOT NULL DEFAULT 0,
+                posted_at INTEGER NOT NULL,
+                UNIQUE (channel, ts)
+            );
+            CREATE INDEX IF NOT EXISTS messages_posted_at ON messages (posted_at);
+            CREATE VIRTUAL TABLE IF NOT EXISTS messages_fts USING fts5(
+                text, content='messages', content_rowid='id', tokenize='trigram'
+            );
+            CREATE TRIGGER IF NOT EXISTS messages_ai AFTER INSERT ON messages BEGIN
+                INSERT INTO messages_fts (rowid, text) VALUES (new.id, new.text);
+            END;
+            CREATE TRIGGER IF NOT EXISTS messages_ad AFTER DELETE ON messages BEGIN
+                INSERT INTO messages_fts (messages_fts, rowid, text) VALUES ('delete', old.id, old.text);
+            END;
+            CREATE TRIGGER IF NOT EXISTS messages_au AFTER UPDATE OF text ON messages BEGIN
+                INSERT INTO messages_fts (messages_fts, rowid, text) VALUES ('delete', old.id, old.text);
+                INSERT INTO messages_fts (rowid, text) VALUES (new.id, new.text);
+            END;",
+        )
+        .map_err(db_error)?;
+        // 表示状態の列がない古いデータベースに列を追加する
+        for column in ["hidden_reason TEXT", "redacted INTEGER NOT NULL DEFAULT 0", "masked INTEGER NOT NULL DEFAULT 0"] {
+            let name = column.split(' ').next().unwrap_or_default();
+            let exists = conn
+                .prepare("SELECT 1 FROM pragma_table_info('messages') WHERE name = ?1")
+                .and_then(|mut stmt| stmt.exists(params![name]))
+                .map_err(db_error)?;
+            if !exists {
+                conn.execute(&format!("ALTER TABLE messages ADD COLUMN {}", column), [])
+                    .map_err(db_error)?;
+            }
+        }
+        let mut store = Self {
+            conn,
+            settings,
+            since_prune: 0,
+        };
+        store.prune(chrono::Utc::now().timestamp())?;
+        Ok(store)
+    }
+
+    pub fn settings(&self) -> &HistorySettings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: HistorySettings) -> Result<(), String> {
+        self.settings = settings;
+        self.prune(chrono::Utc::now().timestamp())
+    }
+
+    /// メッセージを記録する（同じチャンネル・ts のメッセージは上書き）
+    pub fn record(&mut self, record: &HistoryRecord, now: i64) -> Result<(), String> {
+        if !self.settings.enabled {
+            return Ok(());
+        }
+        let posted_at = record.ts.as_deref().and_then(ts_seconds).unwrap_or(now);
+        self.conn
+            .execute(
+                "INSERT INTO messages (channel, channel_name, ts, thread_ts, user_id, user, text, is_system,
+                                       hidden_reason, redacted, masked, posted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                 ON CONFLICT (channel, ts) DO UPDATE SET
+                    channel_name = excluded.channel_name, user = excluded.user, text = excluded.text,
+                    hidden_reason = excluded.hidden_reason, redacted = excluded.redacted, masked = excluded.masked",
+                params![
+                    record.channel,
+                    record.channel_name,
+                    record.ts,
+                    record.thread_ts,
+                    record.user_id,
+                    record.user,
+                    record.text,
+                    record.is_system,
+                    record.hidden_reason,
+                    record.redacted,
+                    record.masked,
+                    posted_at,
+                ],
+            )
+            .map_err(db_error)?;
+
+        self.since_prune += 1;
+        if self.since_prune >= PRUNE_EVERY {
+            self.prune(now)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_reactions(&mut self, channel: &str, ts: &str, reactions: &[ReactionData]) -> Result<(), String> {
+        let json = serde_json::to_string(reactions).map_err(|e| format!("JSON変換エラー: {}", e))?;
+        self.conn
+            .execute(
+                "UPDATE messages SET reactions = ?1 WHERE channel = ?2 AND ts = ?3",
+                params![json, channel, ts],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// 保持期間・件数を超えた古い履歴を削除する
+    fn prune(&mut self, now: i64) -> Result<(), String> {
+        self.since_prune = 0;
+        if self.settings.retention_days > 0 {
+            let cutoff = now - i64::from(self.settings.retention_days) * 86_400;
+            self.conn
+                .execute("DELETE FROM messages WHERE posted_at < ?1", params![cutoff])
+                .map_err(db_error)?;
+        }
+        if self.settings.max_messages > 0 {
+            let oldest_kept: Option<i64> = self
+                .conn
+                .query_row(
+                    "SELECT id FROM messages ORDER BY id DESC LIMIT 1 OFFSET ?1",
+                    params![self.settings.max_messages - 1],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(db_error)?;
+            if let Some(id) = oldest_kept {
+                self.conn
+                    .execute("DELETE FROM messages WHERE id < ?1", params![id])
+                    .map_err(db_error)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 条件に合う履歴を新しい順に返す
+    pub fn search(&self, query: &HistoryQuery) -> Result<Vec<HistoryEntry>, String> {
+        self.select(query, "DESC", Some(query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT)))
+    }
+
+    /// 書き出し用に、条件に合う履歴をすべて古い順に返す
+    pub fn export_entries(&self, query: &HistoryQuery) -> Result<Vec<HistoryEntry>, String> {
+        self.select(query, "ASC", None)
+    }
+
+    fn select(&self, query: &HistoryQuery, order: &str, limit: Option<u32>) -> Result<Vec<HistoryEntry>, String> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+
+        let terms: Vec<&str> = query.query.as_deref().unwrap_or("").split_whitespace().collect();
+        let (long_terms, short_terms): (Vec<&str>, Vec<&str>) = terms.into_iter().partition(|t| t.chars().count() >= 3);
+        if !long_terms.is_empty() {
+            let expr = long_terms
+                .iter()
+                .map(|t| format!("\"{}\"", t.replace('"', "\"\"")))
+                .collect::<Vec<_>>()
+                .join(" ");
+            conditions.push("id IN (SELECT rowid FROM messages_fts WHERE messages_fts MATCH ?)".to_string());
+            values.push(Value::Text(expr));
+        }
+        for term in short_terms {
+            conditions.push("text LIKE ? ESCAPE '\\'".to_string());
+            values.push(Value::Text(format!("%{}%", escape_like(term))));
+        }
+        for (column, list) in [("channel", &query.channels), ("user_id", &query.user_ids)] {
+            if !list.is_empty() {
+                conditions.push(format!("{} IN ({})", column, vec!["?"; list.len()].join(", ")));
+                values.extend(list.iter().cloned().map(Value::Text));
+            }
+        }
+        if !query.include_hidden {
+            conditions.push("hidden_reason IS NULL".to_string());
+        }
+        if let Some(from) = query.from.as_deref().filter(|s| !s.trim().is_empty()) {
+            conditions.push("posted_at >= ?".to_string());
+            values.push(Value::Integer(parse_day(from, false)?));
+        }
+        if let Some(to) = query.to.as_deref().filter(|s| !s.trim().is_empty()) {
+            conditions.push("posted_at < ?".to_string());
+            values.push(Value::Integer(parse_day(to, true)?));
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let limit_clause = limit.map(|n| format!("LIMIT {}", n)).unwrap_or_default();
+        let sql = format!(
+            "SELECT id, channel, channel_name, ts, thread_ts, user_id, user, text, reactions, is_system,
+                    hidden_reason, redacted, masked, posted_at
+             FROM messages {} ORDER BY posted_at {order}, id {order} {}",
+            where_clause, limit_clause
+        );
+
+        let mut stmt = self.conn.prepare(&sql).map_err(db_error)?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                let reactions: String = row.get(8)?;
+                Ok(HistoryEntry {
+                    id: row.get(0)?,
+                    channel: row.get(1)?,
+                    channel_name: row.get(2)?,
+                    ts: row.get(3)?,
+                    thread_ts: row.get(4)?,
+                    user_id: row.get(5)?,
+                    user: row.get(6)?,
+                    text: row.get(7)?,
+                    reactions: serde_json::from_str(&reactions).unwrap_or_default(),
+                    is_system: row.get(9)?,
+                    hidden_reason: row.get(10)?,
+                    redacted: row.get(11)?,
+                    masked: row.get(12)?,
+                    posted_at: row.get(13)?,
+                })
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(db_error)
+    }
+}
+
+/// Tauri で管理する履歴の状態（開けなかった場合は記録しない）
+pub struct HistoryState {
+    store: Mutex<Option<HistoryStore>>,
+}
+
+impl HistoryState {
+    pub fn new(store: Option<HistoryStore>) -> Self {
+        Self {
+            store: Mutex::new(store),
+        }
+    }
+
+    fn with_store<T>(&self, f: impl FnOnce(&mut HistoryStore) -> Result<T, String>) -> Result<T, String> {
+        let mut guard = self.store.lock().map_err(|_| "履歴データベースのロックに失敗しました".to_string())?;
+        match guard.as_mut() {
+            Some(store) => f(store),
+            None => Err("履歴データベースを開けませんでした".to_string()),
+        }
+    }
+
+    /// 受信したメッセージを記録する（失敗してもメッセージの表示は続ける）
+    pub fn record(&self, record: &HistoryRecord) {
+        let now = chrono::Utc::now().timestamp();
+        if let Err(e) = self.with_store(|store| store.record(record, now)) {
+            log::warn!("履歴の記録に失敗: {}", e);
+        }
+    }
+
+    pub fn set_reactions(&self, channel: &str, ts: &str, reactions: &[ReactionData]) {
+        if let Err(e) = self.with_store(|store| store.set_reactions(channel, ts, reactions)) {
+            log::warn!("履歴のリアクション更新に失敗: {}", e);
+        }
+    }
+
+    pub fn settings(&self) -> Result<HistorySettings, String> {
+        self.with_store(|store| Ok(store.settings().clone()))
+    }
+
+    pub fn set_settings(&self, settings: HistorySettings) -> Result<(), String> {
+        self.with_store(|store| store.set_settings(settings))
+    }
+
+    pub fn search(&self, query: &HistoryQuery) -> Result<Vec<HistoryEntry>, String> {
+        self.with_store(|store| store.search(query))
+    }
+
+    pub fn export_entries(&self, query: &HistoryQuery) -> Result<Vec<HistoryEntry>, String> {
+        self.with_store(|store| store.export_entries(query))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{history_enabled, history_record, history_store};
+
+    fn search(store: &HistoryStore, query: HistoryQuery) -> Vec<String> {
+        store.search(&query).unwrap().into_iter().map(|e| e.text).collect()
+    }
+
+    #[test]
+    fn searches_japanese_text_with_filters() {
+        let mut history = history_store(history_enabled());
+        let now = 1_760_000_000;
+        history.record(&history_record("C1", "1760000001.000100", "U1", "明日の定例会議は中止です"), now).unwrap();
+        history.record(&history_record("C2", "1760000002.000100", "U2", "会議室を予約しました"), now).unwrap();
+        history.record(&history_record("C1", "1760000003.000100", "U2", "deploy finished"), now).unwrap();
+        // 同じメッセージの再送は上書き
+        history.record(&history_record("C1", "1760000003.000100", "U2", "deploy finished!"), now).unwrap();
+
+        let text = |q: &str| HistoryQuery {
+            query: Some(q.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(search(&history, text("定例会議")), vec!["明日の定例会議は中止です"]);
+        assert_eq!(search(&history, text("会議")).len(), 2);
+        assert_eq!(search(&history, text("会議 予約")), vec!["会議室を予約しました"]);
+        assert_eq!(search(&history, text("finished")), vec!["deploy finished!"]);
+        assert_eq!(
+            search(&history, HistoryQuery { channels: vec!["C1".to_string()], user_ids: vec!["U2".to_string()], ..Default::default() }),
+            vec!["deploy finished!"]
+        );
+
+        let reactions = vec![ReactionData {
+            name: "eyes".to_string(),
+            count: 1,
+            users: vec!["U1".to_string()],
+        }];
+        history.set_reactions("C2", "1760000002.000100", &reactions).unwrap();
+        let entry = &history.search(&text("予約")).unwrap()[0];
+        assert_eq!(entry.reactions, reactions);
+        assert_eq!(entry.posted_at, 1_760_000_002);
+    }
+
+    #[test]
+    fn stores_plain_text_with_display_state() {
+        let mut history = history_store(history_enabled());
+        let now = 1_760_000_000;
+        let mut hidden = history_record(
+            "C1",
+            "1760000001.000100",
+            "U1",
+            &plain_text(r#"<span class="slack-mention">@佐藤</span> span &lt;b&gt; &amp; &quot;c&quot; &#39;d&#39;"#),
+        );
+        hidden.hidden_reason = Some("filter".to_string());
+        history.record(&hidden, now).unwrap();
+
+        // 表示用のマークアップは検索対象にしない
+        let text = |q: &str| HistoryQuery {
+            query: Some(q.to_string()),
+            include_hidden: true,
+            ..Default::default()
+        };
+        assert!(history.search(&text("slack-mention")).unwrap().is_empty());
+        let entries = history.search(&text("佐藤")).unwrap();
+        assert_eq!(entries[0].text, r#"@佐藤 span <b> & "c" 'd'"#);
+        assert_eq!(entries[0].hidden_reason.as_deref(), Some("filter"));
+        // 表示しなかったメッセージは指定しない限り検索・書き出しに含めない
+        assert!(history.search(&HistoryQuery::default()).unwrap().is_empty());
+        assert!(history.export_entries(&HistoryQuery::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn prunes_by_age_and_count() {
+        let mut history = history_store(HistorySettings {
+            enabled: true,
+            retention_days: 1,
+            max_messages: 2,
+        });
+        let now = 1_760_000_000;
+        history.record(&history_record("C1", "1759900000.000000", "U1", "old"), now).unwrap();
+        for (i, text) in ["a", "b", "c"].iter().enumerate() {
+            history.record(&history_record("C1", &format!("17600000{:02}.000000", i), "U1", text), now).unwrap();
+        }
+        history.prune(now).unwrap();
+        assert_eq!(search(&history, HistoryQuery::default()), vec!["c", "b"]);
+
+        history
+            .set_settings(HistorySettings {
+                enabled: false,
+                retention_days: 0,
+                max_messages: 0,
+            })
+            .unwrap();
+        history.record(&history_record("C1", "1760000100.000000", "U1", "ignored"), now).unwrap();
+        assert_eq!(search(&history, HistoryQuery::default()).len(), 2);
+    }
+}
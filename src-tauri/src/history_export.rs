@@ -0,0 +1,232 @@
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::history::HistoryEntry;
+
+/// 履歴の書き出し形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// 1行1件の JSON
+    Jsonl,
+    /// Excel で開ける UTF-8（BOM 付き）の CSV
+    Csv,
+    /// チャンネル・スレッドごとにまとめた議事録
+    Markdown,
+}
+
+/// 書き出す範囲と形式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryExportRequest {
+    /// 対象のチャンネルID（空ならすべて）
+    #[serde(default)]
+    pub channels: Vec<String>,
+    /// "YYYY-MM-DD"（ローカル日付、両端を含む）
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    pub format: ExportFormat,
+    /// 書き出し先のファイル
+    pub path: String,
+}
+
+fn format_time(posted_at: i64, pattern: &str) -> String {
+    Local
+        .timestamp_opt(posted_at, 0)
+        .single()
+        .map(|t| t.format(pattern).to_string())
+        .unwrap_or_default()
+}
+
+fn channel_label(entry: &HistoryEntry) -> String {
+    entry
+        .channel_name
+        .clone()
+        .or_else(|| entry.channel.clone())
+        .unwrap_or_else(|| "(チャンネルなし)".to_string())
+}
+
+/// リアクションを ":eyes: 2 :+1: 1" の形にする
+fn reaction_summary(entry: &HistoryEntry) -> String {
+    entry
+        .reactions
+        .iter()
+        .map(|r| format!(":{}: {}", r.name, r.count))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn render(entries: &[HistoryEntry], format: ExportFormat) -> Result<String, String> {
+    match format {
+        ExportFormat::Jsonl => render_jsonl(entries),
+        ExportFormat::Csv => Ok(render_csv(entries)),
+        ExportFormat::Markdown => Ok(render_markdown(entries)),
+    }
+}
+
+fn render_jsonl(entries: &[HistoryEntry]) -> Result<String, String> {
+    let mut out = String::new();
+    for entry in entries {
+        out.push_str(&serde_json::to_string(entry).map_err(|e| format!("JSON変換エラー: {}", e))?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn render_csv(entries: &[HistoryEntry]) -> String {
+    let mut out = String::from("\u{feff}日時,チャンネル,チャンネルID,ユーザー,ユーザーID,スレッド,テキスト,リアクション\r\n");
+    for entry in entries {
+        let row = [
+            format_time(entry.posted_at, "%Y-%m-%d %H:%M:%S"),
+            channel_label(entry),
+            entry.channel.clone().unwrap_or_default(),
+            entry.user.clone(),
+            entry.user_id.clone().unwrap_or_default(),
+            entry.thread_ts.clone().unwrap_or_default(),
+            entry.text.clone(),
+            reaction_summary(entry),
+        ];
+        out.push_str(&row.iter().map(|v| csv_field(v)).collect::<Vec<_>>().join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn markdown_item(entry: &HistoryEntry, indent: &str) -> String {
+    let reactions = reaction_summary(entry);
+    let text = entry.text.replace('\n', &format!("\n{}  ", indent));
+    let mut line = format!(
+        "{}- **{}** {}: {}",
+        indent,
+        format_time(entry.posted_at, "%Y-%m-%d %H:%M"),
+        entry.user,
+        text
+    );
+    if !reactions.is_empty() {
+        line.push_str(&format!(" ({})", reactions));
+    }
+    line.push('\n');
+    line
+}
+
+/// スレッドの親の ts と、そのスレッドのメッセージ
+type ThreadGroup<'a> = (Option<String>, Vec<&'a HistoryEntry>);
+
+/// チャンネルごとに見出しを付け、スレッドの返信は親メッセージの下に字下げして並べる
+fn render_markdown(entries: &[HistoryEntry]) -> String {
+    let mut channels: Vec<String> = Vec::new();
+    // チャンネル → スレッド（親の ts、ts のないシステムメッセージは None で単独）→ メッセージ
+    let mut threads: HashMap<String, Vec<ThreadGroup>> = HashMap::new();
+    for entry in entries {
+        let label = channel_label(entry);
+        if !threads.contains_key(&label) {
+            channels.push(label.clone());
+        }
+        let key = entry.thread_ts.clone().or_else(|| entry.ts.clone());
+        let groups = threads.entry(label).or_default();
+        match groups.iter_mut().find(|(k, _)| key.is_some() && *k == key) {
+            Some((_, messages)) => messages.push(entry),
+            None => groups.push((key, vec![entry])),
+        }
+    }
+
+    let mut out = String::from("# メッセージ履歴\n");
+    for label in channels {
+        out.push_str(&format!("\n## #{}\n\n", label.trim_start_matches('#')));
+        for (key, messages) in &threads[&label] {
+            let Some(key) = key else {
+                out.push_str(&markdown_item(messages[0], ""));
+                continue;
+            };
+            let (root, replies): (Vec<&HistoryEntry>, Vec<&HistoryEntry>) =
+                messages.iter().partition(|m| m.ts.as_deref() == Some(key.as_str()));
+            match root.first() {
+                Some(root) => out.push_str(&markdown_item(root, "")),
+                // 親メッセージが範囲外のスレッド
+                None => out.push_str("- （スレッドの返信）\n"),
+            }
+            for reply in replies {
+                out.push_str(&markdown_item(reply, "  "));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::slack_client::ReactionData;
+
+    fn entry(ts: &str, thread_ts: Option<&str>, channel: &str, text: &str) -> HistoryEntry {
+        HistoryEntry {
+            id: 0,
+            channel: Some(channel.to_string()),
+            channel_name: Some(format!("#{}", channel.to_lowercase())),
+            ts: Some(ts.to_string()),
+            thread_ts: thread_ts.map(str::to_string),
+            user_id: Some("U1".to_string()),
+            user: "山田".to_string(),
+            text: text.to_string(),
+            reactions: Vec::new(),
+            is_system: false,
+            hidden_reason: None,
+            redacted: false,
+            masked: false,
+            posted_at: ts.split('.').next().unwrap().parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn csv_has_bom_and_quotes_fields() {
+        let mut first = entry("1760000000.000100", None, "C1", "改行\nと \"引用\", カンマ");
+        first.reactions = vec![ReactionData {
+            name: "eyes".to_string(),
+            count: 2,
+            users: Vec::new(),
+        }];
+        let csv = render(&[first], ExportFormat::Csv).unwrap();
+        assert!(csv.starts_with("\u{feff}日時,"));
+        assert!(csv.contains(",#c1,C1,山田,U1,,\"改行\nと \"\"引用\"\", カンマ\",:eyes: 2\r\n"));
+    }
+
+    #[test]
+    fn markdown_groups_threads_under_channels() {
+        let mut system = entry("1760000004.000100", None, "C1", "山田さんが参加しました");
+        system.ts = None;
+        system.user = "システム".to_string();
+        let entries = vec![
+            entry("1760000000.000100", None, "C1", "親メッセージ"),
+            entry("1760000001.000100", None, "C2", "別チャンネル"),
+            entry("1760000002.000100", Some("1760000000.000100"), "C1", "返信"),
+            entry("1760000003.000100", Some("1750000000.000100"), "C1", "古いスレッドへの返信"),
+            system,
+        ];
+        let markdown = render(&entries, ExportFormat::Markdown).unwrap();
+        let c1 = markdown.find("## #c1").unwrap();
+        let c2 = markdown.find("## #c2").unwrap();
+        assert!(c1 < c2);
+        let section = &markdown[c1..c2];
+        assert!(section.contains("山田: 親メッセージ\n  - **"));
+        assert!(section.contains("- （スレッドの返信）\n  - **"));
+        assert!(section.contains("古いスレッドへの返信\n- **"));
+        // ts のないメッセージはスレッドの返信扱いにせず、単独の項目にする
+        assert_eq!(section.matches("（スレッドの返信）").count(), 1);
+        let system_line = section.lines().find(|l| l.contains("参加しました")).unwrap();
+        assert!(system_line.starts_with("- **"));
+
+        let jsonl = render(&entries, ExportFormat::Jsonl).unwrap();
+        assert_eq!(jsonl.lines().count(), 5);
+    }
+}
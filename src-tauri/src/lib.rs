@@ -1,8 +1,23 @@
+mod anonymize;
 mod commands;
+mod event_recorder;
+mod filters;
+mod highlight;
+mod history;
+mod history_export;
+mod message_queue;
+mod ng_words;
+mod pause;
+mod redaction;
+mod replay;
+mod schedule;
 mod slack_client;
 mod storage;
+#[cfg(test)]
+mod test_support;
+mod throttle;
 
-use commands::{config, slack};
+use commands::{config, queue, slack};
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -18,10 +33,41 @@ pub fn run() {
 
             // SlackClientの状態を管理
             let slack_state = slack_client::SlackClientState::new();
-            app.manage(slack_state);
 
             // StorageStateの管理
             let storage_state = storage::StorageState::new(app_data_dir);
+
+            // 監視チャンネルやルールなどは画面を介さずバックエンドで復元する
+            let stored_config = storage_state.load_config().unwrap_or_else(|e| {
+                log::warn!("設定の読み込みに失敗: {}", e);
+                None
+            });
+            if let Some(config) = stored_config.clone() {
+                tauri::async_runtime::block_on(slack_state.restore_config(config));
+            }
+
+            // NGワード辞書は最初のメッセージより前に読み込んでおく
+            match storage_state.load_ng_words() {
+                Ok(entries) => tauri::async_runtime::block_on(slack_state.set_ng_words(entries)),
+                Err(e) => log::warn!("NGワード辞書の読み込みに失敗: {}", e),
+            }
+            // 前回終了時のキューを復元
+            let queue = storage_state.load_queue().unwrap_or_else(|e| {
+                log::warn!("キューの読み込みに失敗: {}", e);
+                Default::default()
+            });
+            app.manage(message_queue::MessageQueueState::new(queue));
+            // メッセージ履歴のデータベースを開く（開けなくても表示は続ける）
+            let history_settings = stored_config
+                .as_ref()
+                .and_then(|c| c.history.clone())
+                .unwrap_or_default();
+            let history_store = history::HistoryStore::open(&storage_state.history_path(), history_settings)
+                .map_err(|e| log::error!("履歴データベースを開けませんでした: {}", e))
+                .ok();
+            app.manage(history::HistoryState::new(history_store));
+            slack_state.start_schedule_timer(app.handle().clone());
+            app.manage(slack_state);
             app.manage(storage_state);
 
             log::info!("Leaner Waigaya 起動完了");
@@ -41,7 +87,50 @@ pub fn run() {
             slack::slack_get_channel_info,
             slack::slack_get_watched_channels,
             slack::get_current_channel_name,
+            slack::slack_add_channel_rule,
+            slack::slack_remove_channel_rule,
+            slack::slack_get_channel_rules,
+            slack::slack_apply_channel_rules,
+            slack::slack_validate_channels,
+            slack::slack_get_channel_settings,
+            slack::slack_set_channel_settings,
+            slack::slack_add_filter_rule,
+            slack::slack_remove_filter_rule,
+            slack::slack_get_filter_rules,
+            slack::slack_test_filter_rule,
+            slack::slack_get_filter_stats,
+            slack::slack_add_highlight_rule,
+            slack::slack_remove_highlight_rule,
+            slack::slack_get_highlight_rules,
+            slack::slack_get_pause_state,
+            slack::slack_pause_stream,
+            slack::slack_resume_stream,
+            slack::slack_get_schedule,
+            slack::slack_set_schedule,
+            slack::slack_get_schedule_state,
+            slack::slack_get_event_recording,
+            slack::slack_set_event_recording,
+            slack::replay_get_state,
+            slack::replay_start,
+            slack::replay_stop,
+            slack::slack_get_throttle_metrics,
+            slack::slack_get_anonymous_mode,
+            slack::slack_set_anonymous_mode,
+            slack::slack_get_ng_words,
+            slack::slack_add_ng_word,
+            slack::slack_remove_ng_word,
+            slack::slack_import_ng_words,
+            slack::slack_export_ng_words,
+            slack::slack_get_redaction_settings,
+            slack::slack_set_redaction_settings,
+            slack::slack_get_thread,
+            slack::slack_watch_thread,
+            slack::slack_unwatch_thread,
+            slack::slack_get_watched_threads,
             slack::slack_reload_users,
+            slack::slack_follow_user,
+            slack::slack_unfollow_user,
+            slack::slack_get_followed_users,
             slack::get_users_count,
             slack::slack_get_custom_emojis,
             slack::save_emojis_data,
@@ -50,6 +139,22 @@ pub fn run() {
             slack::get_cache_status,
             slack::get_emoji_url,
             slack::get_emojis_last_updated,
+            // Queue commands
+            queue::queue_get_state,
+            queue::queue_add_message,
+            queue::queue_add_text,
+            queue::queue_remove,
+            queue::queue_play,
+            queue::queue_select,
+            queue::queue_pause,
+            queue::queue_resume,
+            queue::queue_stop,
+            queue::queue_clear,
+            // History commands
+            commands::history::search_history,
+            commands::history::get_history_settings,
+            commands::history::set_history_settings,
+            commands::history::export_history,
         ])
         .build(tauri::generate_context!())
         .expect("error while running tauri application")
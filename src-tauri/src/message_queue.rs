@@ -0,0 +1,497 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::sync::Mutex;
+
+use crate::slack_client::{ImageData, ReactionData, SlackMessage};
+use crate::storage::StorageState;
+
+/// キューに保持するメッセージ数の上限（超えたら古いものから削除）
+pub const MAX_QUEUE_SIZE: usize = 50;
+
+/// 1件あたりの表示時間
+const DISPLAY_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 変更をまとめてファイルに保存するまでの待ち時間
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueItem {
+    pub id: u64,
+    #[serde(flatten)]
+    pub message: SlackMessage,
+    /// 参加・退出の通知やダイジェストなどのシステムメッセージ
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_system: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reactions: Vec<ReactionData>,
+    /// キューに追加した時刻（Unix ミリ秒）
+    pub added_at: u64,
+}
+
+impl QueueItem {
+    fn is_for(&self, channel: &str, ts: &str) -> bool {
+        self.message.channel.as_deref() == Some(channel) && self.message.timestamp.as_deref() == Some(ts)
+    }
+}
+
+/// 再生位置の通知（`queue-position` イベント）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuePosition {
+    pub current_index: Option<usize>,
+    pub is_playing: bool,
+}
+
+/// 表示待ちのメッセージキュー（`queue-updated` イベントでそのまま送り、ファイルにも保存する）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageQueue {
+    pub items: Vec<QueueItem>,
+    /// 表示中（または最後に表示した）位置。items.len() と等しい場合は末尾まで再生済み
+    pub current_index: Option<usize>,
+    pub is_playing: bool,
+    #[serde(default)]
+    next_id: u64,
+    /// 利用者が一時停止した（新着メッセージが来ても再開しない）
+    #[serde(skip)]
+    paused: bool,
+}
+
+impl MessageQueue {
+    pub fn position(&self) -> QueuePosition {
+        QueuePosition {
+            current_index: self.current_index,
+            is_playing: self.is_playing,
+        }
+    }
+
+    pub fn current(&self) -> Option<&QueueItem> {
+        self.current_index.and_then(|i| self.items.get(i))
+    }
+
+    /// 保存用の複製（画像の data URL はファイルを肥大化させるため保存しない）
+    pub fn without_images(&self) -> Self {
+        let mut queue = self.clone();
+        for item in &mut queue.items {
+            item.message.images = None;
+            item.message.reply_to_images = None;
+        }
+        queue
+    }
+
+    /// 追加して即座に表示位置をそのメッセージへ移す（一時停止中は位置を変えずに末尾へ積む）
+    pub fn push(&mut self, message: SlackMessage, is_system: bool, added_at: u64) -> u64 {
+        if self.items.len() >= MAX_QUEUE_SIZE {
+            self.items.remove(0);
+            log::info!("キューサイズ制限により古いメッセージを削除（最大{}件）", MAX_QUEUE_SIZE);
+        }
+        self.next_id += 1;
+        self.items.push(QueueItem {
+            id: self.next_id,
+            message,
+            is_system,
+            reactions: Vec::new(),
+            added_at,
+        });
+        if !self.paused {
+            self.current_index = Some(self.items.len() - 1);
+            self.is_playing = true;
+        }
+        self.next_id
+    }
+
+    /// 後から取得した画像を該当メッセージに添付する
+    pub fn attach_images(&mut self, channel: &str, ts: &str, images: Vec<ImageData>, reply_to: bool) -> bool {
+        let Some(item) = self.items.iter_mut().find(|item| item.is_for(channel, ts)) else {
+            return false;
+        };
+        if reply_to {
+            item.message.reply_to_images = Some(images);
+        } else {
+            item.message.images = Some(images);
+        }
+        true
+    }
+
+    pub fn set_reactions(&mut self, channel: &str, ts: &str, reactions: Vec<ReactionData>) -> bool {
+        let Some(item) = self.items.iter_mut().find(|item| item.is_for(channel, ts)) else {
+            return false;
+        };
+        item.reactions = reactions;
+        true
+    }
+
+    /// 削除する。表示中のメッセージを消した場合は同じ位置（次のメッセージ）から表示を続ける
+    pub fn remove(&mut self, id: u64) -> bool {
+        let Some(index) = self.items.iter().position(|item| item.id == id) else {
+            return false;
+        };
+        self.items.remove(index);
+        if let Some(current) = self.current_index {
+            if index < current {
+                self.current_index = Some(current - 1);
+            } else if index == current && current >= self.items.len() {
+                self.is_playing = false;
+            }
+        }
+        true
+    }
+
+    /// 次のメッセージへ進める。末尾に達したら再生を止めて None を返す
+    pub fn advance(&mut self) -> Option<&QueueItem> {
+        let next = self.current_index.map_or(0, |i| i + 1);
+        self.current_index = Some(next.min(self.items.len()));
+        if next >= self.items.len() {
+            self.is_playing = false;
+            return None;
+        }
+        self.items.get(next)
+    }
+
+    /// 先頭（または停止した位置）から再生する
+    pub fn play(&mut self) -> bool {
+        if self.items.is_empty() {
+            return false;
+        }
+        if self.current_index.is_none_or(|i| i >= self.items.len()) {
+            self.current_index = Some(0);
+        }
+        self.is_playing = true;
+        self.paused = false;
+        true
+    }
+
+    /// 指定位置から再生する
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.items.len() {
+            return false;
+        }
+        self.current_index = Some(index);
+        self.is_playing = true;
+        self.paused = false;
+        true
+    }
+
+    pub fn pause(&mut self) {
+        self.is_playing = false;
+        self.paused = true;
+    }
+
+    /// 一時停止した位置から再開する（末尾まで再生済みなら何もしない）
+    pub fn resume(&mut self) -> bool {
+        if self.is_playing || self.current_index.is_some_and(|i| i >= self.items.len()) {
+            self.paused = false;
+            return false;
+        }
+        self.play()
+    }
+
+    pub fn stop(&mut self) {
+        self.is_playing = false;
+        self.current_index = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.stop();
+        self.items.clear();
+    }
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// キューの状態と再生タイマー
+pub struct MessageQueueState {
+    queue: Arc<Mutex<MessageQueue>>,
+    /// 再生タイマーの世代（新しく再生を始めると古いタイマーは止まる）
+    generation: Arc<AtomicU64>,
+    /// 保存待ちの変更がある
+    save_pending: Arc<AtomicBool>,
+}
+
+impl MessageQueueState {
+    /// 保存済みのキューから復元する（再生は止めた状態で始める）
+    pub fn new(mut queue: MessageQueue) -> Self {
+        queue.is_playing = false;
+        Self {
+            queue: Arc::new(Mutex::new(queue)),
+            generation: Arc::new(AtomicU64::new(0)),
+            save_pending: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub async fn snapshot(&self) -> MessageQueue {
+        self.queue.lock().await.clone()
+    }
+
+    /// 内容が変わったキューの保存を予約して queue-updated を通知し、再生中なら現在位置から表示し直す
+    async fn commit(&self, app_handle: &tauri::AppHandle, restart: bool) {
+        let snapshot = self.queue.lock().await.clone();
+        self.schedule_save(app_handle);
+        let _ = app_handle.emit("queue-updated", &snapshot);
+        if restart {
+            self.restart_playback(app_handle);
+        }
+    }
+
+    /// 少し待ってから、その時点のキューをまとめて保存する（ファイル書き込みは別スレッドで行う）
+    fn schedule_save(&self, app_handle: &tauri::AppHandle) {
+        if self.save_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let queue = self.queue.clone();
+        let save_pending = self.save_pending.clone();
+        let app_handle = app_handle.clone();
+
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(SAVE_DELAY).await;
+            save_pending.store(false, Ordering::SeqCst);
+            let snapshot = queue.lock().await.without_images();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                app_handle.state::<StorageState>().save_queue(&snapshot)
+            })
+            .await;
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => log::warn!("キューの保存に失敗: {}", e),
+                Err(e) => log::warn!("キューの保存に失敗: {}", e),
+            }
+        });
+    }
+
+    /// 再生位置だけが変わったときの通知
+    async fn notify_position(&self, app_handle: &tauri::AppHandle) {
+        let position = self.queue.lock().await.position();
+        let _ = app_handle.emit("queue-position", &position);
+    }
+
+    /// 現在位置を表示し、一定間隔で次へ進めるタイマーを起動する（既存のタイマーは止める）
+    fn restart_playback(&self, app_handle: &tauri::AppHandle) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let current_generation = self.generation.clone();
+        let queue = self.queue.clone();
+        let app_handle = app_handle.clone();
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                {
+                    let q = queue.lock().await;
+                    if current_generation.load(Ordering::SeqCst) != generation || !q.is_playing {
+                        break;
+                    }
+                    if let Some(item) = q.current() {
+                        log::info!("メッセージ表示: {}/{}", q.current_index.unwrap_or(0) + 1, q.items.len());
+                        let _ = app_handle.emit("display-slack-message", item);
+                    }
+                    let _ = app_handle.emit("queue-position", &q.position());
+                }
+
+                tokio::time::sleep(DISPLAY_INTERVAL).await;
+
+                let mut q = queue.lock().await;
+                if current_generation.load(Ordering::SeqCst) != generation {
+                    break;
+                }
+                if q.advance().is_none() {
+                    log::info!("キュー再生完了");
+                    let _ = app_handle.emit("queue-position", &q.position());
+                    break;
+                }
+            }
+        });
+    }
+
+    fn stop_playback(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub async fn push(&self, message: SlackMessage, app_handle: &tauri::AppHandle) -> u64 {
+        let is_system = message.queue_action.as_deref() == Some("addSystemMessage");
+        let (id, is_playing) = {
+            let mut queue = self.queue.lock().await;
+            let id = queue.push(message, is_system, now_millis());
+            (id, queue.is_playing)
+        };
+        self.commit(app_handle, is_playing).await;
+        id
+    }
+
+    /// 画像を添付し、表示ウィンドウにも追送する
+    pub async fn attach_images(
+        &self,
+        channel: &str,
+        ts: &str,
+        images: Vec<ImageData>,
+        reply_to: bool,
+        app_handle: &tauri::AppHandle,
+    ) {
+        let update = serde_json::json!({
+            "channel": channel,
+            "timestamp": ts,
+            "images": images,
+            "replyTo": reply_to,
+        });
+        if self.queue.lock().await.attach_images(channel, ts, images, reply_to) {
+            self.commit(app_handle, false).await;
+            let _ = app_handle.emit("display-message-images-update", &update);
+        } else {
+            log::info!("画像追送スキップ: キューに該当メッセージなし ch={} ts={}", channel, ts);
+        }
+    }
+
+    pub async fn set_reactions(&self, channel: &str, ts: &str, reactions: Vec<ReactionData>, app_handle: &tauri::AppHandle) {
+        if self.queue.lock().await.set_reactions(channel, ts, reactions) {
+            self.commit(app_handle, false).await;
+        }
+    }
+
+    pub async fn remove(&self, id: u64, app_handle: &tauri::AppHandle) -> bool {
+        let (removed, was_current, is_playing) = {
+            let mut queue = self.queue.lock().await;
+            let was_current = queue.current().is_some_and(|item| item.id == id);
+            (queue.remove(id), was_current, queue.is_playing)
+        };
+        if removed {
+            // 表示中のメッセージを消したら、古いタイマーを止めて次のメッセージから表示し直す
+            if was_current && !is_playing {
+                self.stop_playback();
+            }
+            self.commit(app_handle, was_current && is_playing).await;
+        }
+        removed
+    }
+
+    pub async fn play(&self, app_handle: &tauri::AppHandle) -> bool {
+        let started = self.queue.lock().await.play();
+        if started {
+            self.restart_playback(app_handle);
+        }
+        started
+    }
+
+    pub async fn select(&self, index: usize, app_handle: &tauri::AppHandle) -> bool {
+        let selected = self.queue.lock().await.select(index);
+        if selected {
+            self.restart_playback(app_handle);
+        }
+        selected
+    }
+
+    pub async fn pause(&self, app_handle: &tauri::AppHandle) {
+        self.stop_playback();
+        self.queue.lock().await.pause();
+        self.notify_position(app_handle).await;
+    }
+
+    pub async fn resume(&self, app_handle: &tauri::AppHandle) -> bool {
+        let resumed = self.queue.lock().await.resume();
+        if resumed {
+            self.restart_playback(app_handle);
+        }
+        resumed
+    }
+
+    pub async fn stop(&self, app_handle: &tauri::AppHandle) {
+        self.stop_playback();
+        self.queue.lock().await.stop();
+        self.notify_position(app_handle).await;
+    }
+
+    pub async fn clear(&self, app_handle: &tauri::AppHandle) {
+        self.stop_playback();
+        self.queue.lock().await.clear();
+        self.commit(app_handle, false).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(channel: &str, ts: &str) -> SlackMessage {
+        serde_json::from_value(serde_json::json!({
+            "text": format!("message {}", ts),
+            "user": "山田",
+            "userIcon": "",
+            "channel": channel,
+            "timestamp": ts,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn push_trims_oldest_and_plays_newest() {
+        let mut queue = MessageQueue::default();
+        for i in 0..MAX_QUEUE_SIZE + 2 {
+            queue.push(message("C1", &i.to_string()), false, 0);
+        }
+        assert_eq!(queue.items.len(), MAX_QUEUE_SIZE);
+        assert_eq!(queue.items[0].message.timestamp.as_deref(), Some("2"));
+        assert_eq!(queue.current_index, Some(MAX_QUEUE_SIZE - 1));
+        assert!(queue.is_playing);
+
+        // 末尾まで再生したら止まり、再開しても何もしない
+        assert!(queue.advance().is_none());
+        assert!(!queue.is_playing);
+        assert!(!queue.resume());
+        assert!(queue.play());
+        assert_eq!(queue.current_index, Some(0));
+    }
+
+    #[test]
+    fn removing_current_moves_to_next_and_pause_holds_position() {
+        let mut queue = MessageQueue::default();
+        let first = queue.push(message("C1", "1.0"), false, 0);
+        let second = queue.push(message("C1", "2.0"), false, 0);
+        queue.push(message("C1", "3.0"), false, 0);
+        assert!(queue.select(0));
+
+        // 表示中を消すと次のメッセージが同じ位置に来る
+        assert!(queue.remove(first));
+        assert_eq!(queue.current().map(|item| item.id), Some(second));
+        assert!(queue.is_playing);
+
+        // 一時停止中の新着は位置を動かさず、再生も始めない
+        queue.pause();
+        queue.push(message("C1", "4.0"), false, 0);
+        assert_eq!(queue.current().map(|item| item.id), Some(second));
+        assert!(!queue.is_playing);
+        assert!(queue.resume());
+        queue.push(message("C1", "5.0"), false, 0);
+        assert_eq!(queue.current_index, Some(3));
+    }
+
+    #[test]
+    fn attach_remove_and_round_trip() {
+        let mut queue = MessageQueue::default();
+        let first = queue.push(message("C1", "1.0"), false, 0);
+        queue.push(message("C1", "2.0"), true, 0);
+        let images = vec![ImageData {
+            data_url: "data:image/png;base64,AA==".to_string(),
+            name: None,
+        }];
+        assert!(queue.attach_images("C1", "2.0", images, false));
+        assert!(!queue.attach_images("C2", "2.0", Vec::new(), false));
+
+        assert!(queue.remove(first));
+        assert_eq!(queue.current_index, Some(0));
+        assert!(!queue.remove(first));
+
+        let json = serde_json::to_value(queue.without_images()).unwrap();
+        assert_eq!(json["items"][0]["isSystem"], true);
+        assert_eq!(json["items"][0]["timestamp"], "2.0");
+        let restored: MessageQueue = serde_json::from_value(json).unwrap();
+        assert!(restored.items[0].message.images.is_none());
+        assert_eq!(restored.next_id, 2);
+        // 保存用の複製を作っても表示中のキューの画像は残す
+        assert_eq!(queue.items[0].message.images.as_ref().map(Vec::len), Some(1));
+    }
+}
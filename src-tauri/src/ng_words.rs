@@ -0,0 +1,216 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// NGワードを置き換える文字列
+pub const MASK: &str = "●●●";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NgWordEntry {
+    pub word: String,
+    /// 伏せ字にせずメッセージ全体を表示しない
+    #[serde(default)]
+    pub hide_message: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NgWordFormat {
+    /// 1行1語（`#` で始まる行はコメント）
+    Text,
+    /// `word,action`（action は mask / hide）
+    Csv,
+}
+
+/// NGワード辞書（照合用の正規表現をまとめて保持）
+#[derive(Default)]
+pub struct NgWordDictionary {
+    entries: Vec<NgWordEntry>,
+    matcher: Option<Regex>,
+    /// 小文字化した語 → メッセージごと非表示にするか
+    hide: HashMap<String, bool>,
+}
+
+/// 伏せ字にすると表示が壊れる箇所（HTMLタグ・文字参照）
+fn markup_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"<[^>]*>|&[a-zA-Z0-9#]+;").unwrap())
+}
+
+impl NgWordDictionary {
+    pub fn new(entries: Vec<NgWordEntry>) -> Self {
+        let mut words: Vec<&str> = entries
+            .iter()
+            .map(|e| e.word.as_str())
+            .filter(|w| !w.is_empty())
+            .collect();
+        // 長い語を優先して一致させる
+        words.sort_by_key(|w| std::cmp::Reverse(w.chars().count()));
+        let matcher = if words.is_empty() {
+            None
+        } else {
+            let alternation = words.iter().map(|w| regex::escape(w)).collect::<Vec<_>>().join("|");
+            Regex::new(&format!("(?i)(?:{})", alternation)).ok()
+        };
+        let hide = entries
+            .iter()
+            .map(|e| (e.word.to_lowercase(), e.hide_message))
+            .collect();
+
+        Self {
+            entries,
+            matcher,
+            hide,
+        }
+    }
+
+    pub fn entries(&self) -> &[NgWordEntry] {
+        &self.entries
+    }
+
+    /// メッセージ全体を非表示にする語を含むか
+    pub fn hides(&self, text: &str) -> bool {
+        let Some(matcher) = &self.matcher else {
+            return false;
+        };
+        matcher
+            .find_iter(text)
+            .any(|m| self.hide.get(&m.as_str().to_lowercase()).copied().unwrap_or(false))
+    }
+
+    /// 表示用テキスト（HTMLを含む）のNGワードを伏せ字にする。タグ・文字参照の内側は置き換えない。
+    pub fn mask(&self, text: &str) -> String {
+        let Some(matcher) = &self.matcher else {
+            return text.to_string();
+        };
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+        for markup in markup_regex().find_iter(text) {
+            result.push_str(&matcher.replace_all(&text[last..markup.start()], MASK));
+            result.push_str(markup.as_str());
+            last = markup.end();
+        }
+        result.push_str(&matcher.replace_all(&text[last..], MASK));
+        result
+    }
+}
+
+/// CSV の1行をフィールドに分割する（`"` で囲んだフィールドと `""` のエスケープに対応）
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// インポートするテキストを解析する
+pub fn parse(content: &str, format: NgWordFormat) -> Result<Vec<NgWordEntry>, String> {
+    let mut entries = Vec::new();
+    let content = content.trim_start_matches('\u{feff}');
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (format == NgWordFormat::Text && line.starts_with('#')) {
+            continue;
+        }
+        match format {
+            NgWordFormat::Text => entries.push(NgWordEntry {
+                word: line.to_string(),
+                hide_message: false,
+            }),
+            NgWordFormat::Csv => {
+                let fields = split_csv_line(line);
+                let word = fields[0].trim();
+                let action = fields.get(1).map(|a| a.trim().to_lowercase()).unwrap_or_default();
+                if i == 0 && word.eq_ignore_ascii_case("word") {
+                    continue;
+                }
+                let hide_message = match action.as_str() {
+                    "" | "mask" => false,
+                    "hide" => true,
+                    other => return Err(format!("{}行目: 不明な動作です: {}", i + 1, other)),
+                };
+                if !word.is_empty() {
+                    entries.push(NgWordEntry {
+                        word: word.to_string(),
+                        hide_message,
+                    });
+                }
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// 辞書をテキストに書き出す
+pub fn export(entries: &[NgWordEntry], format: NgWordFormat) -> String {
+    match format {
+        NgWordFormat::Text => entries.iter().map(|e| format!("{}\n", e.word)).collect(),
+        NgWordFormat::Csv => {
+            let mut out = String::from("word,action\n");
+            for entry in entries {
+                let action = if entry.hide_message { "hide" } else { "mask" };
+                out.push_str(&format!("{},{}\n", csv_field(&entry.word), action));
+            }
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ng_word;
+
+    #[test]
+    fn masks_outside_markup_only() {
+        let dict = NgWordDictionary::new(vec![ng_word("ばか", false), ng_word("Span", false)]);
+        let text = r#"<span class="slack-mention">@ばか太郎</span> SPAN ばかばか"#;
+        assert_eq!(
+            dict.mask(text),
+            r#"<span class="slack-mention">@●●●太郎</span> ●●● ●●●●●●"#
+        );
+        assert!(!dict.hides(text));
+    }
+
+    #[test]
+    fn hide_entries_hide_whole_message() {
+        let dict = NgWordDictionary::new(vec![ng_word("damn", false), ng_word("禁止語", true)]);
+        assert!(dict.hides("これは禁止語です"));
+        assert!(!dict.hides("Damn it"));
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let entries = vec![ng_word("a,b", true), ng_word("say \"hi\"", false)];
+        let csv = export(&entries, NgWordFormat::Csv);
+        assert_eq!(parse(&csv, NgWordFormat::Csv).unwrap(), entries);
+        assert!(parse("word,action\nfoo,delete\n", NgWordFormat::Csv).is_err());
+        assert_eq!(
+            parse("# comment\nfoo\n\nbar\n", NgWordFormat::Text).unwrap(),
+            vec![ng_word("foo", false), ng_word("bar", false)]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 一時停止中に保持するメッセージの上限（超えたら古いものから捨てる）
+pub const PAUSE_BUFFER_CAPACITY: usize = 200;
+
+/// 再開時に溜まったメッセージをどう扱うか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResumeMode {
+    /// すべてそのまま流す
+    All,
+    /// チャンネルごとの件数だけを流す
+    Summary,
+    /// 捨てる
+    Discard,
+}
+
+/// 一時停止のきっかけ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseSource {
+    /// 利用者の操作
+    Manual,
+    /// スケジュールの「一時停止」の時間帯
+    Schedule,
+}
+
+/// 一時停止の状態（`stream-pause-state` イベントで通知）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseState {
+    pub paused: bool,
+    /// スケジュールによる一時停止を含む
+    pub scheduled: bool,
+    /// 保持しているメッセージ数
+    pub buffered: usize,
+    /// 上限を超えて捨てたメッセージ数
+    pub dropped: usize,
+}
+
+/// 一時停止中のメッセージを溜めておくバッファ
+pub struct PauseBuffer<T> {
+    manual: bool,
+    scheduled: bool,
+    messages: VecDeque<T>,
+    dropped: usize,
+}
+
+impl<T> Default for PauseBuffer<T> {
+    fn default() -> Self {
+        Self {
+            manual: false,
+            scheduled: false,
+            messages: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+}
+
+impl<T> PauseBuffer<T> {
+    pub fn state(&self) -> PauseState {
+        PauseState {
+            paused: self.is_paused(),
+            scheduled: self.scheduled,
+            buffered: self.messages.len(),
+            dropped: self.dropped,
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.manual || self.scheduled
+    }
+
+    pub fn pause(&mut self, source: PauseSource) {
+        match source {
+            PauseSource::Manual => self.manual = true,
+            PauseSource::Schedule => self.scheduled = true,
+        }
+    }
+
+    /// 一時停止中なら溜めて None、そうでなければそのまま返す
+    pub fn hold(&mut self, message: T) -> Option<T> {
+        if !self.is_paused() {
+            return Some(message);
+        }
+        if self.messages.len() >= PAUSE_BUFFER_CAPACITY {
+            self.messages.pop_front();
+            self.dropped += 1;
+        }
+        self.messages.push_back(message);
+        None
+    }
+
+    /// 溜めているメッセージのうち条件に合う最後のもの（後から届いた画像の添付用）
+    pub fn find_mut(&mut self, pred: impl Fn(&T) -> bool) -> Option<&mut T> {
+        self.messages.iter_mut().rev().find(|m| pred(m))
+    }
+
+    /// 再開し、溜めていたメッセージと捨てた件数を返す。
+    /// 手動の再開はスケジュールによる停止も解除するが、スケジュールの再開は手動の停止を解除しない。
+    /// スケジュールで止めていなかった場合や、まだ止めておく場合は None
+    pub fn resume(&mut self, source: PauseSource) -> Option<(Vec<T>, usize)> {
+        match source {
+            PauseSource::Manual => {
+                self.manual = false;
+                self.scheduled = false;
+            }
+            PauseSource::Schedule if !self.scheduled => return None,
+            PauseSource::Schedule => self.scheduled = false,
+        }
+        if self.is_paused() {
+            return None;
+        }
+        let dropped = std::mem::take(&mut self.dropped);
+        Some((self.messages.drain(..).collect(), dropped))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_while_paused_and_drops_oldest() {
+        let mut buffer = PauseBuffer::default();
+        assert_eq!(buffer.hold(0), Some(0));
+
+        buffer.pause(PauseSource::Manual);
+        for i in 0..PAUSE_BUFFER_CAPACITY + 5 {
+            assert_eq!(buffer.hold(i), None);
+        }
+        assert_eq!(
+            buffer.state(),
+            PauseState {
+                paused: true,
+                scheduled: false,
+                buffered: PAUSE_BUFFER_CAPACITY,
+                dropped: 5,
+            }
+        );
+
+        let (messages, dropped) = buffer.resume(PauseSource::Manual).unwrap();
+        assert_eq!(messages.first(), Some(&5));
+        assert_eq!(messages.len(), PAUSE_BUFFER_CAPACITY);
+        assert_eq!(dropped, 5);
+        assert_eq!(buffer.state(), PauseState::default());
+    }
+
+    #[test]
+    fn schedule_resume_keeps_manual_pause() {
+        let mut buffer = PauseBuffer::default();
+        // スケジュールで止めていなければ、スケジュールの再開では何もしない
+        buffer.pause(PauseSource::Manual);
+        assert!(buffer.resume(PauseSource::Schedule).is_none());
+        assert!(buffer.state().paused);
+
+        buffer.pause(PauseSource::Schedule);
+        assert_eq!(buffer.hold(1), None);
+        assert!(buffer.resume(PauseSource::Schedule).is_none());
+        assert!(buffer.state().paused);
+        assert!(!buffer.state().scheduled);
+
+        // 手動の再開はスケジュールによる停止もまとめて解除する
+        buffer.pause(PauseSource::Schedule);
+        assert_eq!(buffer.resume(PauseSource::Manual), Some((vec![1], 0)));
+        assert_eq!(buffer.state(), PauseState::default());
+
+        buffer.pause(PauseSource::Schedule);
+        assert_eq!(buffer.resume(PauseSource::Schedule), Some((Vec::new(), 0)));
+    }
+}
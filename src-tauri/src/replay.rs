@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::event_recorder::RecordedEvent;
+
+/// リプレイの進み具合（`replay-state` イベントで通知）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayState {
+    pub running: bool,
+    /// 処理したイベント数
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// リプレイする1件（offset はリプレイ開始からの待ち時間）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayEvent {
+    pub offset: Duration,
+    pub envelope: Value,
+}
+
+/// 記録ファイル（JSONL）を読み、受信間隔を speed 倍に縮めた再生予定を返す。
+/// 記録形式でない行はエンベロープそのものとみなし、直前のイベントと同時に流す
+pub fn load(content: &str, speed: f64) -> Result<Vec<ReplayEvent>, String> {
+    if !(speed.is_finite() && speed > 0.0) {
+        return Err(format!("再生速度は0より大きい値で指定してください: {}", speed));
+    }
+
+    let mut events = Vec::new();
+    let mut first_at: Option<u64> = None;
+    let mut offset = Duration::ZERO;
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(line).map_err(|e| format!("{}行目を読み込めません: {}", i + 1, e))?;
+        let envelope = match serde_json::from_value::<RecordedEvent>(value.clone()) {
+            Ok(recorded) => {
+                let start = *first_at.get_or_insert(recorded.recorded_at);
+                let elapsed = recorded.recorded_at.saturating_sub(start);
+                offset = Duration::from_millis(elapsed).div_f64(speed).max(offset);
+                recorded.envelope
+            }
+            Err(_) => value,
+        };
+        events.push(ReplayEvent { offset, envelope });
+    }
+    if events.is_empty() {
+        return Err("リプレイするイベントがありません".to_string());
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_recorded_timing() {
+        let content = r#"
+{"recordedAt":1000,"decision":"hello","envelope":{"type":"hello"}}
+{"recordedAt":3000,"decision":"displayed","envelope":{"type":"events_api"}}
+{"type":"events_api","payload":{}}
+{"recordedAt":11000,"decision":"reaction","envelope":{"type":"events_api"}}
+"#;
+        let events = load(content, 2.0).unwrap();
+        let offsets: Vec<u64> = events.iter().map(|e| e.offset.as_millis() as u64).collect();
+        assert_eq!(offsets, vec![0, 1000, 1000, 5000]);
+        assert_eq!(events[0].envelope["type"], "hello");
+        assert_eq!(events[2].envelope["payload"], serde_json::json!({}));
+
+        assert!(load(content, 0.0).is_err());
+        assert!(load("", 1.0).is_err());
+        assert!(load("{\"recordedAt\":", 1.0).unwrap_err().starts_with("1行目"));
+    }
+}
@@ -0,0 +1,227 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::pause::ResumeMode;
+
+/// 表示を止める時間帯の動作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum QuietAction {
+    /// 一時停止してメッセージを溜める
+    #[default]
+    Pause,
+    /// メンション（ボット宛て・@here 等）以外を表示しない
+    MentionsOnly,
+}
+
+/// 曜日と時刻の範囲（end が start より前なら日付をまたぐ）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyRange {
+    /// 1=月曜 … 7=日曜（空なら毎日）
+    #[serde(default)]
+    pub weekdays: Vec<u32>,
+    /// "HH:MM"
+    pub start: String,
+    pub end: String,
+}
+
+/// 一度だけスケジュールを適用しない期間（"YYYY-MM-DDTHH:MM"）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleExclusion {
+    pub start: String,
+    pub end: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScheduleSettings {
+    pub enabled: bool,
+    /// 表示する時間帯（空なら終日）
+    pub working_hours: Vec<WeeklyRange>,
+    /// 定例会議など、表示しない時間帯
+    pub quiet_hours: Vec<WeeklyRange>,
+    /// 終日表示しない日（"YYYY-MM-DD"）
+    pub holidays: Vec<String>,
+    pub exclusions: Vec<ScheduleExclusion>,
+    pub quiet_action: QuietAction,
+    /// 一時停止から戻るときの溜めたメッセージの扱い
+    pub resume_mode: ResumeMode,
+}
+
+impl Default for ScheduleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            working_hours: Vec::new(),
+            quiet_hours: Vec::new(),
+            holidays: Vec::new(),
+            exclusions: Vec::new(),
+            quiet_action: QuietAction::Pause,
+            resume_mode: ResumeMode::Summary,
+        }
+    }
+}
+
+/// スケジュールで決まる表示状態（`schedule-state` イベントで通知）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleState {
+    /// 表示を止める時間帯か
+    pub quiet: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<QuietAction>,
+    /// 止めている理由（勤務時間外・休日・時間帯）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| format!("時刻は HH:MM で指定してください: {}", value))
+}
+
+fn parse_date_time(value: &str) -> Result<NaiveDateTime, String> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%dT%H:%M")
+        .map_err(|_| format!("日時は YYYY-MM-DDTHH:MM で指定してください: {}", value))
+}
+
+impl WeeklyRange {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(day) = self.weekdays.iter().find(|d| !(1..=7).contains(*d)) {
+            return Err(format!("曜日は 1（月）〜 7（日）で指定してください: {}", day));
+        }
+        parse_time(&self.start)?;
+        parse_time(&self.end)?;
+        Ok(())
+    }
+
+    fn includes_day(&self, date: NaiveDate) -> bool {
+        self.weekdays.is_empty() || self.weekdays.contains(&date.weekday().number_from_monday())
+    }
+
+    fn contains(&self, now: NaiveDateTime) -> bool {
+        let (Ok(start), Ok(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let time = now.time();
+        if start <= end {
+            self.includes_day(now.date()) && start <= time && time < end
+        } else {
+            // 日付をまたぐ範囲は開始した日の曜日で判定する
+            (self.includes_day(now.date()) && time >= start)
+                || (now.date().pred_opt().is_some_and(|d| self.includes_day(d)) && time < end)
+        }
+    }
+}
+
+impl ScheduleSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        for range in self.working_hours.iter().chain(&self.quiet_hours) {
+            range.validate()?;
+        }
+        for holiday in &self.holidays {
+            NaiveDate::parse_from_str(holiday.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("休日は YYYY-MM-DD で指定してください: {}", holiday))?;
+        }
+        for exclusion in &self.exclusions {
+            parse_date_time(&exclusion.start)?;
+            parse_date_time(&exclusion.end)?;
+        }
+        Ok(())
+    }
+
+    /// 現在時刻（ローカル時刻）の表示状態を判定する
+    pub fn evaluate(&self, now: NaiveDateTime) -> ScheduleState {
+        if !self.enabled {
+            return ScheduleState::default();
+        }
+        let excluded = self.exclusions.iter().any(|e| {
+            matches!((parse_date_time(&e.start), parse_date_time(&e.end)), (Ok(s), Ok(end)) if s <= now && now < end)
+        });
+        if excluded {
+            return ScheduleState::default();
+        }
+
+        let today = now.date().format("%Y-%m-%d").to_string();
+        let reason = if self.holidays.iter().any(|h| h.trim() == today) {
+            Some("休日")
+        } else if !self.working_hours.is_empty() && !self.working_hours.iter().any(|r| r.contains(now)) {
+            Some("勤務時間外")
+        } else if self.quiet_hours.iter().any(|r| r.contains(now)) {
+            Some("表示しない時間帯")
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => ScheduleState {
+                quiet: true,
+                action: Some(self.quiet_action),
+                reason: Some(reason.to_string()),
+            },
+            None => ScheduleState::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        parse_date_time(value).unwrap()
+    }
+
+    fn range(weekdays: &[u32], start: &str, end: &str) -> WeeklyRange {
+        WeeklyRange {
+            weekdays: weekdays.to_vec(),
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    #[test]
+    fn working_hours_holidays_and_exclusions() {
+        let settings = ScheduleSettings {
+            enabled: true,
+            working_hours: vec![range(&[1, 2, 3, 4, 5], "09:00", "18:00")],
+            quiet_hours: vec![range(&[1], "10:00", "11:00")],
+            holidays: vec!["2026-10-21".to_string()],
+            exclusions: vec![ScheduleExclusion {
+                start: "2026-10-24T20:00".to_string(),
+                end: "2026-10-24T23:00".to_string(),
+                label: Some("リリース".to_string()),
+            }],
+            ..Default::default()
+        };
+        settings.validate().unwrap();
+
+        // 2026-10-19 は月曜日
+        assert!(!settings.evaluate(at("2026-10-19T09:30")).quiet);
+        assert_eq!(settings.evaluate(at("2026-10-19T10:30")).reason.as_deref(), Some("表示しない時間帯"));
+        assert_eq!(settings.evaluate(at("2026-10-20T18:00")).reason.as_deref(), Some("勤務時間外"));
+        assert_eq!(settings.evaluate(at("2026-10-21T12:00")).reason.as_deref(), Some("休日"));
+        // 土曜の夜でも例外期間は表示する
+        assert!(settings.evaluate(at("2026-10-24T19:59")).quiet);
+        assert!(!settings.evaluate(at("2026-10-24T21:00")).quiet);
+    }
+
+    #[test]
+    fn overnight_range_uses_start_day() {
+        let settings = ScheduleSettings {
+            enabled: true,
+            quiet_hours: vec![range(&[5], "22:00", "06:00")],
+            quiet_action: QuietAction::MentionsOnly,
+            ..Default::default()
+        };
+        // 金曜 22:00 〜 土曜 06:00
+        assert!(settings.evaluate(at("2026-10-23T23:00")).quiet);
+        assert_eq!(settings.evaluate(at("2026-10-24T05:59")).action, Some(QuietAction::MentionsOnly));
+        assert!(!settings.evaluate(at("2026-10-24T23:00")).quiet);
+        assert!(!settings.evaluate(at("2026-10-23T05:00")).quiet);
+        assert!(range(&[8], "09:00", "10:00").validate().is_err());
+    }
+}
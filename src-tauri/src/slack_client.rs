@@ -1,13 +1,24 @@
+use crate::anonymize::Anonymizer;
+use crate::filters::{FilterDecision, FilterRule, FilterSet};
+use crate::highlight::{Highlight, HighlightRule, HighlightSet};
+use crate::ng_words::{NgWordDictionary, NgWordEntry, NgWordFormat};
+use crate::pause::{PauseBuffer, PauseSource, PauseState, ResumeMode};
+use crate::redaction::RedactionSettings;
+use crate::event_recorder::{EventRecorder, RecorderStatus};
+use crate::history::{HistoryEntry, HistoryRecord, HistorySettings};
+use crate::replay::ReplayState;
+use crate::schedule::{QuietAction, ScheduleSettings, ScheduleState};
+use crate::throttle::{BurstGovernor, ThrottleMetrics};
 use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, OnceLock};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use tokio::sync::RwLock;
 
 // === 型定義 (TypeScript types.ts に対応) ===
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SlackConfig {
     #[serde(default)]
@@ -18,6 +29,139 @@ pub struct SlackConfig {
     pub channels: Vec<String>,
     #[serde(default)]
     pub watched_channel_data: HashMap<String, SlackChannel>,
+    /// スレッド返信に添える親メッセージの最大文字数（0 で省略しない）
+    #[serde(default = "default_thread_parent_max_chars")]
+    pub thread_parent_max_chars: usize,
+    /// チャンネル監視とは独立して返信を表示するスレッド
+    #[serde(default)]
+    pub watched_threads: Vec<WatchedThread>,
+    /// チャンネルを問わず発言を表示するユーザーID
+    #[serde(default)]
+    pub followed_users: Vec<String>,
+    /// チャンネル名のパターンに一致するチャンネルを自動で監視に追加するルール
+    #[serde(default)]
+    pub channel_rules: Vec<ChannelRule>,
+    /// チャンネルごとの設定（キーはチャンネルID）
+    #[serde(default)]
+    pub channel_settings: HashMap<String, ChannelSettings>,
+    /// リアクション数の合計がこの値に達したメッセージを再表示する（0 で無効）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trending_threshold: Option<u32>,
+    /// 表示前に適用するメッセージフィルタ（全体・チャンネル別）
+    #[serde(default)]
+    pub filter_rules: Vec<FilterRule>,
+    /// キーワード・正規表現に一致した箇所を強調するルール
+    #[serde(default)]
+    pub highlight_rules: Vec<HighlightRule>,
+    /// 表示前にメールアドレス・電話番号・トークン等をマスクする（チャンネル設定で上書き可）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionSettings>,
+    /// 発言者名・アイコン・メンションを仮名に置き換えて表示する
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anonymous_mode: Option<bool>,
+    /// チャンネルごとの1分あたりの表示上限（超えた分はダイジェストにまとめる。0 で無制限）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_messages_per_minute: Option<u32>,
+    /// 勤務時間外・会議中などに表示を止めるスケジュール
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<ScheduleSettings>,
+    /// 受信したメッセージの履歴の保存設定
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<HistorySettings>,
+}
+
+pub(crate) fn default_thread_parent_max_chars() -> usize {
+    50
+}
+
+impl Default for SlackConfig {
+    fn default() -> Self {
+        Self {
+            bot_token: String::new(),
+            app_token: String::new(),
+            channels: Vec::new(),
+            watched_channel_data: HashMap::new(),
+            thread_parent_max_chars: default_thread_parent_max_chars(),
+            watched_threads: Vec::new(),
+            followed_users: Vec::new(),
+            channel_rules: Vec::new(),
+            channel_settings: HashMap::new(),
+            trending_threshold: None,
+            filter_rules: Vec::new(),
+            highlight_rules: Vec::new(),
+            redaction: None,
+            anonymous_mode: None,
+            max_messages_per_minute: None,
+            schedule: None,
+            history: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelSettings {
+    /// メンバーの参加・退出をシステムメッセージとして表示する
+    #[serde(default)]
+    pub announce_membership: bool,
+    /// このチャンネルだけマスク設定を変える（None で全体設定に従う）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionSettings>,
+    /// このチャンネルだけ1分あたりの表示上限を変える（None で全体設定に従う）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_messages_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelRuleKind {
+    #[default]
+    Glob,
+    Regex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelRule {
+    /// `times-*` のようなglob、または正規表現（先頭の `#` は無視）
+    pub pattern: String,
+    #[serde(default)]
+    pub kind: ChannelRuleKind,
+    /// 未参加のパブリックチャンネルに conversations.join で参加する
+    #[serde(default)]
+    pub auto_join: bool,
+}
+
+impl ChannelRule {
+    fn to_regex(&self) -> Result<regex::Regex, String> {
+        let pattern = self.pattern.trim().trim_start_matches('#');
+        let source = match self.kind {
+            ChannelRuleKind::Glob => {
+                let mut re = String::from("^");
+                for c in pattern.chars() {
+                    match c {
+                        '*' => re.push_str(".*"),
+                        '?' => re.push('.'),
+                        _ => re.push_str(&regex::escape(&c.to_string())),
+                    }
+                }
+                re.push('$');
+                re
+            }
+            ChannelRuleKind::Regex => pattern.to_string(),
+        };
+        regex::RegexBuilder::new(&source)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| format!("パターンが不正です: {}", e))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchedThread {
+    pub channel: String,
+    pub thread_ts: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,12 +172,25 @@ pub struct SlackChannel {
     pub is_private: Option<bool>,
     #[serde(default)]
     pub is_member: Option<bool>,
+    /// DM は "im"、グループDM は "mpim"（通常のチャンネルは None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_archived: Option<bool>,
+}
+
+impl SlackChannel {
+    fn is_direct_message(&self) -> bool {
+        matches!(self.channel_type.as_deref(), Some("im") | Some("mpim"))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlackMessage {
     pub text: String,
     pub user: String,
+    #[serde(rename = "userId", default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     #[serde(rename = "userIcon")]
     pub user_icon: String,
     #[serde(default)]
@@ -48,8 +205,56 @@ pub struct SlackMessage {
     pub reply_to_user: Option<String>,
     #[serde(rename = "replyToText", default)]
     pub reply_to_text: Option<String>,
+    #[serde(rename = "replyToUserIcon", default, skip_serializing_if = "Option::is_none")]
+    pub reply_to_user_icon: Option<String>,
+    #[serde(rename = "replyToImages", default, skip_serializing_if = "Option::is_none")]
+    pub reply_to_images: Option<Vec<ImageData>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub images: Option<Vec<ImageData>>,
+    #[serde(rename = "channelName", default, skip_serializing_if = "Option::is_none")]
+    pub channel_name: Option<String>,
+    /// "channel" / "group" / "im" / "mpim"
+    #[serde(rename = "channelType", default, skip_serializing_if = "Option::is_none")]
+    pub channel_type: Option<String>,
+    /// リアクションが閾値を超えて再表示されたメッセージ
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub trending: bool,
+    /// ハイライトルールに一致した text 中の範囲
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<Highlight>,
+    /// メールアドレス・トークン等をマスクした
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+    /// attachments（リンクのプレビューや連携アプリの投稿）の本文（メンション解決済み）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<String>,
+}
+
+impl SlackMessage {
+    /// Slack 以外から追加するテキストだけのメッセージ
+    pub fn plain(text: String) -> Self {
+        Self {
+            text,
+            user: "System".to_string(),
+            user_id: None,
+            user_icon: String::new(),
+            channel: None,
+            timestamp: None,
+            queue_action: None,
+            thread_ts: None,
+            reply_to_user: None,
+            reply_to_text: None,
+            reply_to_user_icon: None,
+            reply_to_images: None,
+            images: None,
+            channel_name: None,
+            channel_type: None,
+            trending: false,
+            highlights: Vec::new(),
+            redacted: false,
+            attachments: Vec::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,7 +265,7 @@ pub struct ImageData {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct SlackFileObject {
     #[allow(dead_code)]
     id: Option<String>,
@@ -121,6 +326,68 @@ pub struct WatchedChannelsResult {
     pub data: HashMap<String, SlackChannel>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelRuleApplyResult {
+    pub success: bool,
+    pub added: Vec<SlackChannel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 監視チャンネルの状態
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelHealthStatus {
+    Ok,
+    NotMember,
+    Archived,
+    NotFound,
+    /// API エラー等で判定できなかった
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelHealth {
+    pub channel_id: String,
+    pub name: String,
+    pub status: ChannelHealthStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelValidationResult {
+    pub success: bool,
+    pub channels: Vec<ChannelHealth>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterTestResult {
+    pub success: bool,
+    pub matched: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 起動後にフィルタで非表示にしたメッセージ数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FilterStats {
+    pub filtered_count: u64,
+    /// 除外ルールごとの件数（キーはルールID）
+    pub by_rule: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadResult {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub messages: Option<Vec<SlackMessage>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheStatus {
     pub users: usize,
@@ -135,6 +402,8 @@ struct AuthTestResponse {
     #[serde(default)]
     user: Option<String>,
     #[serde(default)]
+    user_id: Option<String>,
+    #[serde(default)]
     error: Option<String>,
 }
 
@@ -152,21 +421,48 @@ struct ConversationsListResponse {
 #[derive(Debug, Deserialize)]
 struct ConversationChannel {
     id: String,
+    #[serde(default)]
     name: String,
     #[serde(default)]
     is_private: bool,
     #[serde(default)]
     is_member: bool,
+    #[serde(default)]
+    is_im: bool,
+    #[serde(default)]
+    is_mpim: bool,
+    #[serde(default)]
+    is_archived: bool,
+    /// DM の相手ユーザーID
+    #[serde(default)]
+    user: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConversationsMembersResponse {
+    ok: bool,
+    #[serde(default)]
+    members: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ConversationsInfoResponse {
-    #[allow(dead_code)]
     ok: bool,
     #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
     channel: Option<ConversationChannel>,
 }
 
+#[derive(Debug, Deserialize)]
+struct ConversationsJoinResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    needed: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ResponseMetadata {
     #[serde(default)]
@@ -205,15 +501,26 @@ struct ConversationsRepliesResponse {
     #[serde(default)]
     messages: Vec<ReplyMessage>,
     #[serde(default)]
+    response_metadata: Option<ResponseMetadata>,
+    #[serde(default)]
     error: Option<String>,
 }
 
+/// conversations.history も conversations.replies と同じ形で返る
+type ConversationsHistoryResponse = ConversationsRepliesResponse;
+
 #[derive(Debug, Deserialize)]
 struct ReplyMessage {
     #[serde(default)]
     text: Option<String>,
     #[serde(default)]
     user: Option<String>,
+    #[serde(default)]
+    ts: Option<String>,
+    #[serde(default)]
+    thread_ts: Option<String>,
+    #[serde(default)]
+    files: Option<Vec<SlackFileObject>>,
 }
 
 // === Socket Mode 関連型 ===
@@ -241,6 +548,9 @@ struct MessageImagesReady {
     channel: String,
     timestamp: String,
     images: Vec<ImageData>,
+    /// true の場合はスレッド親メッセージの画像
+    #[serde(rename = "replyTo", skip_serializing_if = "std::ops::Not::not")]
+    reply_to: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -248,17 +558,34 @@ struct SocketModePayload {
     event: Option<SlackEvent>,
 }
 
+/// イベントの `channel` は通常チャンネルIDだが、channel_created / channel_rename ではオブジェクトになる
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum EventChannel {
+    Id(String),
+    Object {
+        id: String,
+        #[serde(default)]
+        name: Option<String>,
+    },
+}
+
 #[derive(Debug, Deserialize)]
 struct SlackEvent {
     #[serde(rename = "type")]
     event_type: Option<String>,
     #[serde(default)]
     subtype: Option<String>,
-    channel: Option<String>,
+    #[serde(default)]
+    channel: Option<EventChannel>,
+    #[serde(default)]
+    channel_type: Option<String>,
     user: Option<String>,
     text: Option<String>,
     ts: Option<String>,
     thread_ts: Option<String>,
+    #[serde(default)]
+    event_ts: Option<String>,
     #[allow(dead_code)]
     #[serde(default)]
     parent_user_id: Option<String>,
@@ -268,6 +595,56 @@ struct SlackEvent {
     reaction: Option<String>,
     #[serde(default)]
     item: Option<SlackReactionItem>,
+    /// リアクションが付いたメッセージの投稿者
+    #[serde(default)]
+    item_user: Option<String>,
+    #[serde(default)]
+    attachments: Option<Vec<SlackAttachment>>,
+}
+
+impl SlackEvent {
+    fn channel_id(&self) -> Option<&str> {
+        match self.channel.as_ref()? {
+            EventChannel::Id(id) => Some(id),
+            EventChannel::Object { id, .. } => Some(id),
+        }
+    }
+
+    fn channel_name(&self) -> Option<&str> {
+        match self.channel.as_ref()? {
+            EventChannel::Id(_) => None,
+            EventChannel::Object { name, .. } => name.as_deref(),
+        }
+    }
+}
+
+/// リンクのプレビューや連携アプリが付けるメッセージ添付
+#[derive(Debug, Deserialize)]
+struct SlackAttachment {
+    #[serde(default)]
+    pretext: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    fallback: Option<String>,
+}
+
+impl SlackAttachment {
+    /// 表示する本文（前置き・タイトル・本文を改行でつなぎ、どれもなければ fallback）
+    fn body(&self) -> Option<String> {
+        let parts: Vec<&str> = [&self.pretext, &self.title, &self.text]
+            .into_iter()
+            .filter_map(|part| part.as_deref())
+            .filter(|part| !part.is_empty())
+            .collect();
+        if parts.is_empty() {
+            self.fallback.clone().filter(|fallback| !fallback.is_empty())
+        } else {
+            Some(parts.join("\n"))
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -279,6 +656,39 @@ struct SlackReactionItem {
     ts: Option<String>,
 }
 
+/// メッセージに付いたリアクション1種類分の集計
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReactionData {
+    pub name: String,
+    pub count: u32,
+    /// リアクションしたユーザーID
+    #[serde(default)]
+    pub users: Vec<String>,
+}
+
+/// slack-reactions-updated で送るメッセージ単位のリアクション全体
+#[derive(Debug, Clone, Serialize)]
+struct MessageReactions {
+    channel: String,
+    message_ts: String,
+    reactions: Vec<ReactionData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReactionsGetResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    message: Option<ReactionsGetMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReactionsGetMessage {
+    #[serde(default)]
+    reactions: Vec<ReactionData>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct SlackReactionEvent {
     action: String,
@@ -311,6 +721,12 @@ fn translate_slack_error(code: &str) -> String {
 const HTTP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 const IMAGE_FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 const MAX_BACKOFF_SECS: u64 = 60;
+const SCHEDULE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+const PARENT_MESSAGE_CACHE_CAPACITY: usize = 200;
+const REACTION_CACHE_CAPACITY: usize = 500;
+
+/// 画像取得ジョブ（URL, MIMEタイプ, ファイル名）
+type ImageJob = (String, String, Option<String>);
 
 enum SocketRetryOutcome {
     ContinueReconnect,
@@ -358,104 +774,597 @@ async fn check_token_valid(bot_token: &str) -> Result<(), String> {
     }
 }
 
-// === SlackClient 本体 ===
-
-pub struct SlackClientState {
-    inner: Arc<RwLock<SlackClientInner>>,
+/// ユーザー情報から表示用の名前を取得
+fn user_display_name(user_info: &serde_json::Value) -> String {
+    user_info.get("real_name")
+        .or_else(|| user_info.get("name"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string()
 }
 
-struct SlackClientInner {
-    config: SlackConfig,
-    is_connected: bool,
-    watched_channels: std::collections::HashSet<String>,
-    user_cache: HashMap<String, serde_json::Value>,
-    custom_emoji_cache: HashMap<String, String>,
-    current_channel_name: String,
-    socket_cancel: Option<tokio::sync::watch::Sender<bool>>,
-    socket_task: Option<tokio::task::JoinHandle<()>>,
-    socket_generation: u64,
-    last_event_at: Option<std::time::SystemTime>,
+/// ユーザー情報からアイコンURLを取得
+fn user_icon_url(user_info: &serde_json::Value) -> String {
+    user_info.get("profile")
+        .and_then(|p| p.get("image_72").or_else(|| p.get("image_48")))
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
 }
 
-impl SlackClientState {
-    pub fn new() -> Self {
-        Self {
-            inner: Arc::new(RwLock::new(SlackClientInner {
-                config: SlackConfig::default(),
-                is_connected: false,
-                watched_channels: std::collections::HashSet::new(),
-                user_cache: HashMap::new(),
-                custom_emoji_cache: HashMap::new(),
-                current_channel_name: "waigaya".to_string(),
-                socket_cancel: None,
-                socket_task: None,
-                socket_generation: 0,
-                last_event_at: None,
-            })),
+/// 添付ファイルから取得対象の画像を抽出
+fn collect_image_jobs(files: Option<&Vec<SlackFileObject>>) -> Vec<ImageJob> {
+    let mut image_jobs = Vec::new();
+    for file in files.into_iter().flatten() {
+        let mime = file.mimetype.as_deref().unwrap_or("");
+        if !mime.starts_with("image/") {
+            continue;
+        }
+        let url = [
+            file.url_private_download.as_deref(),
+            file.thumb_480.as_deref(),
+            file.thumb_360.as_deref(),
+            file.url_private.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .next();
+        if let Some(url) = url {
+            image_jobs.push((url.to_string(), mime.to_string(), file.name.clone()));
         }
     }
+    image_jobs
+}
 
-    // --- 設定管理 ---
+/// メンション解決済みのテキストを表示文字数で切り詰める。
+/// `<span>` などのタグや `&amp;` 等の文字参照は途中で切らず、開いたままの `<span>` は閉じる。
+fn truncate_rendered_text(text: &str, max_chars: usize) -> String {
+    if max_chars == 0 {
+        return text.to_string();
+    }
 
-    pub async fn update_config(&self, config: SlackConfig) {
-        let mut inner = self.inner.write().await;
-        if !config.channels.is_empty() {
-            log::info!("監視チャンネルを復元: {:?}", config.channels);
-            inner.watched_channels = config.channels.iter().cloned().collect();
+    let mut result = String::new();
+    let mut visible = 0;
+    let mut open_spans = 0usize;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            if let Some(end) = rest.find('>') {
+                let tag = &rest[..=end];
+                if tag.starts_with("</span") {
+                    open_spans = open_spans.saturating_sub(1);
+                } else if tag.starts_with("<span") {
+                    open_spans += 1;
+                }
+                result.push_str(tag);
+                rest = &rest[end + 1..];
+                continue;
+            }
         }
-        inner.config = SlackConfig {
-            bot_token: if config.bot_token.is_empty() {
-                inner.config.bot_token.clone()
-            } else {
-                config.bot_token
-            },
-            app_token: if config.app_token.is_empty() {
-                inner.config.app_token.clone()
-            } else {
-                config.app_token
-            },
-            channels: if config.channels.is_empty() {
-                inner.config.channels.clone()
-            } else {
-                config.channels
-            },
-            watched_channel_data: if config.watched_channel_data.is_empty() {
-                inner.config.watched_channel_data.clone()
-            } else {
-                config.watched_channel_data
-            },
+
+        if visible >= max_chars {
+            break;
+        }
+
+        let unit_len = if c == '&' {
+            rest.find(';')
+                .filter(|&end| end <= 10 && rest[1..end].chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '#'))
+                .map(|end| end + 1)
+                .unwrap_or(1)
+        } else {
+            c.len_utf8()
         };
+        result.push_str(&rest[..unit_len]);
+        rest = &rest[unit_len..];
+        visible += 1;
     }
 
-    pub async fn get_current_channel_name(&self) -> String {
-        self.inner.read().await.current_channel_name.clone()
+    for _ in 0..open_spans {
+        result.push_str("</span>");
     }
+    result
+}
 
-    // --- 接続管理 ---
+/// スレッド親メッセージのキャッシュエントリ
+#[derive(Debug, Clone)]
+struct ParentMessage {
+    user_id: String,
+    text: String,
+    image_jobs: Vec<ImageJob>,
+    /// 取得済みの画像（未取得の場合は None）
+    images: Option<Vec<ImageData>>,
+}
 
-    pub async fn test_connection(&self) -> SlackConnectionResult {
-        let inner = self.inner.read().await;
-        let bot_token = inner.config.bot_token.clone();
-        let app_token = inner.config.app_token.clone();
-        drop(inner);
+/// channel + thread_ts をキーにした親メッセージキャッシュ（容量超過時は古い順に破棄）
+#[derive(Default)]
+struct ParentMessageCache {
+    entries: HashMap<(String, String), ParentMessage>,
+    order: std::collections::VecDeque<(String, String)>,
+}
 
-        if bot_token.is_empty() || app_token.is_empty() {
-            return SlackConnectionResult {
-                success: false,
-                error: Some("Bot Token と App Token が必要です".to_string()),
-            };
+impl ParentMessageCache {
+    fn get(&self, channel: &str, thread_ts: &str) -> Option<&ParentMessage> {
+        self.entries.get(&(channel.to_string(), thread_ts.to_string()))
+    }
+
+    fn insert(&mut self, channel: &str, thread_ts: &str, parent: ParentMessage) {
+        let key = (channel.to_string(), thread_ts.to_string());
+        if self.entries.insert(key.clone(), parent).is_none() {
+            self.order.push_back(key);
         }
+        while self.order.len() > PARENT_MESSAGE_CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
 
-        // Bot Token テスト
-        match self.auth_test(&bot_token).await {
-            Ok(resp) => {
-                if !resp.ok {
-                    return SlackConnectionResult {
-                        success: false,
-                        error: Some(format!("Bot Token認証失敗: {}", resp.error.unwrap_or_default())),
-                    };
+    fn set_images(&mut self, channel: &str, thread_ts: &str, images: Vec<ImageData>) {
+        if let Some(entry) = self.entries.get_mut(&(channel.to_string(), thread_ts.to_string())) {
+            entry.images = Some(images);
+        }
+    }
+}
+
+/// リアクションの追加・削除を集計に反映する（同じユーザーの重複追加は無視）
+fn apply_reaction(reactions: &mut Vec<ReactionData>, name: &str, user: &str, added: bool) {
+    let idx = reactions.iter().position(|r| r.name == name);
+    if added {
+        match idx {
+            Some(i) => {
+                let r = &mut reactions[i];
+                if !r.users.iter().any(|u| u == user) {
+                    r.users.push(user.to_string());
+                    r.count += 1;
                 }
-                log::info!("Bot Token認証成功: {:?}", resp.user);
+            }
+            None => reactions.push(ReactionData {
+                name: name.to_string(),
+                count: 1,
+                users: vec![user.to_string()],
+            }),
+        }
+    } else if let Some(i) = idx {
+        let r = &mut reactions[i];
+        r.users.retain(|u| u != user);
+        r.count = r.count.saturating_sub(1);
+        if r.count == 0 {
+            reactions.remove(i);
+        }
+    }
+}
+
+/// channel + ts をキーにしたリアクション集計（容量超過時は古い順に破棄）
+#[derive(Default)]
+struct ReactionCache {
+    entries: HashMap<(String, String), Vec<ReactionData>>,
+    order: std::collections::VecDeque<(String, String)>,
+    /// 閾値を超えて再表示済みのメッセージ
+    trending: std::collections::HashSet<(String, String)>,
+}
+
+impl ReactionCache {
+    fn insert(&mut self, channel: &str, ts: &str, reactions: Vec<ReactionData>) {
+        let key = (channel.to_string(), ts.to_string());
+        if self.entries.insert(key.clone(), reactions).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > REACTION_CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+                self.trending.remove(&oldest);
+            }
+        }
+    }
+
+    /// 再表示済みとして記録し、初めてなら true を返す
+    fn mark_trending(&mut self, channel: &str, ts: &str) -> bool {
+        self.trending.insert((channel.to_string(), ts.to_string()))
+    }
+
+    fn contains(&self, channel: &str, ts: &str) -> bool {
+        self.entries.contains_key(&(channel.to_string(), ts.to_string()))
+    }
+
+    /// 新着メッセージをリアクションなしとして登録（既に集計済みなら何もしない）
+    fn track(&mut self, channel: &str, ts: &str) {
+        if !self.entries.contains_key(&(channel.to_string(), ts.to_string())) {
+            self.insert(channel, ts, Vec::new());
+        }
+    }
+
+    /// 集計済みのメッセージなら変更を反映して最新の集計を返す
+    fn apply(&mut self, channel: &str, ts: &str, name: &str, user: &str, added: bool) -> Option<Vec<ReactionData>> {
+        let reactions = self.entries.get_mut(&(channel.to_string(), ts.to_string()))?;
+        apply_reaction(reactions, name, user, added);
+        Some(reactions.clone())
+    }
+}
+
+/// 画像のファイル名をマスクし、マスクした箇所の数を返す
+fn redact_image_names(images: &mut [ImageData], settings: Option<&RedactionSettings>) -> usize {
+    let Some(settings) = settings else {
+        return 0;
+    };
+    let mut total = 0;
+    for name in images.iter_mut().filter_map(|image| image.name.as_mut()) {
+        let (redacted, count) = crate::redaction::redact(name, settings);
+        *name = redacted;
+        total += count;
+    }
+    total
+}
+
+/// 一時停止中に溜めたメッセージをチャンネルごとの件数のシステムメッセージにまとめる
+fn summarize_held_messages(messages: &[SlackMessage]) -> Vec<SlackMessage> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut groups: HashMap<&str, Vec<&SlackMessage>> = HashMap::new();
+    for message in messages {
+        let channel = message.channel.as_deref().unwrap_or("");
+        if !groups.contains_key(channel) {
+            order.push(channel);
+        }
+        groups.entry(channel).or_default().push(message);
+    }
+
+    order
+        .into_iter()
+        .map(|channel| {
+            let group = &groups[channel];
+            let people: std::collections::HashSet<&str> = group
+                .iter()
+                .filter(|m| m.queue_action.as_deref() != Some("addSystemMessage"))
+                .map(|m| m.user_id.as_deref().unwrap_or(m.user.as_str()))
+                .collect();
+            let channel_name = group.iter().find_map(|m| m.channel_name.clone());
+            let label = channel_name.as_deref().unwrap_or(channel);
+            let text = format!("#{}: 一時停止中のメッセージ {}件（{}人）", label, group.len(), people.len());
+            SlackMessage {
+                user: "Slack".to_string(),
+                channel: Some(channel.to_string()).filter(|c| !c.is_empty()),
+                queue_action: Some("addSystemMessage".to_string()),
+                channel_name,
+                ..SlackMessage::plain(text)
+            }
+        })
+        .collect()
+}
+
+/// スレッド返信に添える親メッセージ情報
+struct ReplyContext {
+    user: String,
+    user_icon: String,
+    text: String,
+    /// 本文をマスクした
+    redacted: bool,
+    images: Option<Vec<ImageData>>,
+    /// 未取得の親メッセージ画像（バックグラウンドで取得して追送する）
+    pending_image_jobs: Vec<ImageJob>,
+}
+
+/// 受信メッセージを判定・加工した結果
+struct PreparedMessage {
+    /// 履歴に残す内容（実名・プレーンテキスト。非表示の理由もここに入る）
+    record: HistoryRecord,
+    /// 画面に送るメッセージ（非表示・本文なしは None）
+    message: Option<SlackMessage>,
+    /// 本文・添付・画像のいずれかがある
+    has_content: bool,
+    image_jobs: Vec<ImageJob>,
+    parent_image_jobs: Vec<ImageJob>,
+    /// 後から届く画像のファイル名に使うマスク設定
+    redaction: Option<RedactionSettings>,
+}
+
+// === SlackClient 本体 ===
+
+pub struct SlackClientState {
+    inner: Arc<RwLock<SlackClientInner>>,
+}
+
+struct SlackClientInner {
+    config: SlackConfig,
+    is_connected: bool,
+    watched_channels: std::collections::HashSet<String>,
+    user_cache: HashMap<String, serde_json::Value>,
+    custom_emoji_cache: HashMap<String, String>,
+    current_channel_name: String,
+    socket_cancel: Option<tokio::sync::watch::Sender<bool>>,
+    socket_task: Option<tokio::task::JoinHandle<()>>,
+    socket_generation: u64,
+    last_event_at: Option<std::time::SystemTime>,
+    parent_message_cache: ParentMessageCache,
+    reaction_cache: ReactionCache,
+    filter_stats: FilterStats,
+    /// config.filter_rules をコンパイルしたもの
+    filters: FilterSet,
+    /// config.highlight_rules をコンパイルしたもの
+    highlights: HighlightSet,
+    ng_words: NgWordDictionary,
+    anonymizer: Anonymizer,
+    burst_governor: BurstGovernor,
+    pause: PauseBuffer<SlackMessage>,
+    /// 最後に判定したスケジュールの状態
+    schedule_state: ScheduleState,
+    /// 受信したエンベロープの記録（調査用）。書き込み中にクライアントのロックを握らないよう別に持つ
+    recorder: Arc<std::sync::Mutex<EventRecorder>>,
+    /// 実行中のリプレイを止める
+    replay_cancel: Option<tokio::sync::watch::Sender<bool>>,
+    replay_generation: u64,
+    replay_state: ReplayState,
+    channel_name_cache: HashMap<String, String>,
+    bot_user_id: Option<String>,
+}
+
+impl SlackClientInner {
+    fn insert_watched_channel(&mut self, channel: SlackChannel) {
+        self.watched_channels.insert(channel.id.clone());
+        self.config.channels = self.watched_channels.iter().cloned().collect();
+
+        // 最初のチャンネルならチャンネル名を更新
+        if self.watched_channels.len() == 1 {
+            self.current_channel_name = channel.name.clone();
+        }
+        self.config.watched_channel_data.insert(channel.id.clone(), channel);
+    }
+
+    /// 監視チャンネル・監視スレッドへの返信・フォロー中ユーザーの発言のいずれかかどうか
+    fn is_watched_message(&self, channel: &str, thread_ts: Option<&str>, user: Option<&str>) -> bool {
+        self.watched_channels.contains(channel)
+            || user.is_some_and(|u| self.config.followed_users.iter().any(|f| f == u))
+            || thread_ts.is_some_and(|tts| {
+                self.config
+                    .watched_threads
+                    .iter()
+                    .any(|t| t.channel == channel && t.thread_ts == tts)
+            })
+    }
+
+    /// リアクションを集計する対象か（監視対象のメッセージ、または表示済みのメッセージ）
+    fn is_reaction_target(&self, channel: &str, ts: &str, item_user: Option<&str>) -> bool {
+        self.is_watched_message(channel, None, item_user) || self.reaction_cache.contains(channel, ts)
+    }
+
+    /// フィルタルールで判定し、表示してよければ true を返す（非表示にした件数を記録）
+    fn passes_filters(&mut self, channel: &str, user_id: Option<&str>, text: &str) -> bool {
+        let decision = self.filters.evaluate(channel, user_id, text);
+        if decision == FilterDecision::Pass {
+            return true;
+        }
+        self.filter_stats.filtered_count += 1;
+        if let FilterDecision::Excluded(rule_id) = decision {
+            *self.filter_stats.by_rule.entry(rule_id).or_default() += 1;
+        }
+        false
+    }
+
+    /// チャンネルに適用するマスク設定（無効なら None）
+    fn redaction_settings(&self, channel: &str) -> Option<&RedactionSettings> {
+        self.config
+            .channel_settings
+            .get(channel)
+            .and_then(|s| s.redaction.as_ref())
+            .or(self.config.redaction.as_ref())
+            .filter(|s| s.enabled)
+    }
+
+    /// 本文・attachments・添付ファイル名をマスクする（親メッセージの本文は fetch_reply_context で切り詰め前にマスク済み）
+    fn apply_redaction(&self, message: &mut SlackMessage) {
+        let Some(settings) = self.redaction_settings(message.channel.as_deref().unwrap_or("")) else {
+            return;
+        };
+        let (text, count) = crate::redaction::redact_rendered(&message.text, settings);
+        message.text = text;
+        let mut total = count;
+        for attachment in message.attachments.iter_mut() {
+            let (redacted, count) = crate::redaction::redact_rendered(attachment, settings);
+            *attachment = redacted;
+            total += count;
+        }
+        for images in [message.images.as_mut(), message.reply_to_images.as_mut()].into_iter().flatten() {
+            total += redact_image_names(images, Some(settings));
+        }
+        message.redacted |= total > 0;
+    }
+
+    /// チャンネルに適用する1分あたりの表示上限（None で無制限）
+    fn rate_limit(&self, channel: &str) -> Option<u32> {
+        self.config
+            .channel_settings
+            .get(channel)
+            .and_then(|s| s.max_messages_per_minute)
+            .or(self.config.max_messages_per_minute)
+            .filter(|limit| *limit > 0)
+    }
+
+    /// メンションのみ表示する時間帯に、メンションを含まないメッセージなら true
+    fn schedule_hides(&self, text: &str) -> bool {
+        if self.schedule_state.action != Some(QuietAction::MentionsOnly) {
+            return false;
+        }
+        let mentions_bot = self
+            .bot_user_id
+            .as_deref()
+            .is_some_and(|id| text.contains(&format!("<@{}", id)));
+        let broadcast = ["<!here", "<!channel", "<!everyone"].iter().any(|m| text.contains(m));
+        !(mentions_bot || broadcast)
+    }
+
+    /// 表示前の判定（フィルタ → NGワード → スケジュール）。表示しない場合はその理由を返す。
+    /// フィルタと NGワードは画面に出す文面（メンション解決済み）で、メンションの有無は生テキストで判定する
+    fn hidden_reason(&mut self, channel: &str, user_id: Option<&str>, raw_text: &str, rendered: &str) -> Option<&'static str> {
+        let text = crate::history::plain_text(rendered);
+        if !self.passes_filters(channel, user_id, &text) {
+            Some("filter")
+        } else if self.ng_words.hides(&text) {
+            Some("ng_word")
+        } else if self.schedule_hides(raw_text) {
+            Some("schedule")
+        } else {
+            None
+        }
+    }
+
+    /// 受信したメッセージの非表示の理由。表示できるものだけを流量上限の件数に数える
+    fn screen_message(
+        &mut self,
+        channel: &str,
+        user_id: Option<&str>,
+        raw_text: &str,
+        rendered: &str,
+        now: std::time::Instant,
+    ) -> Option<&'static str> {
+        if let Some(reason) = self.hidden_reason(channel, user_id, raw_text, rendered) {
+            return Some(reason);
+        }
+        match self.rate_limit(channel) {
+            Some(limit) if !self.burst_governor.admit(channel, user_id, limit, now) => Some("throttled"),
+            _ => None,
+        }
+    }
+
+    /// 後から取得したメッセージ（スレッド表示）を受信時と同じフィルタ・NGワードで判定する（件数は数えない）
+    fn hides_fetched(&self, channel: &str, user_id: Option<&str>, rendered: &str) -> bool {
+        let text = crate::history::plain_text(rendered);
+        self.filters.evaluate(channel, user_id, &text) != FilterDecision::Pass || self.ng_words.hides(&text)
+    }
+
+    fn is_anonymous(&self) -> bool {
+        self.config.anonymous_mode.unwrap_or(false)
+    }
+
+    /// 表示する発言者名とアイコン（匿名モードでは仮名と生成アイコン）
+    fn present_user(&mut self, user_id: &str, user_info: &serde_json::Value) -> (String, String) {
+        if self.is_anonymous() {
+            (self.anonymizer.alias(user_id), crate::anonymize::identicon(user_id))
+        } else {
+            (user_display_name(user_info), user_icon_url(user_info))
+        }
+    }
+
+    /// 本文と親メッセージの本文のNGワードを伏せ字にする
+    fn apply_ng_words(&self, message: &mut SlackMessage) {
+        message.text = self.ng_words.mask(&message.text);
+        if let Some(reply_to_text) = message.reply_to_text.as_mut() {
+            *reply_to_text = self.ng_words.mask(reply_to_text);
+        }
+        for attachment in message.attachments.iter_mut() {
+            *attachment = self.ng_words.mask(attachment);
+        }
+    }
+
+    /// 送信直前の表示用テキストにハイライトルールを適用する
+    fn apply_highlights(&self, message: &mut SlackMessage) {
+        let channel = message.channel.as_deref().unwrap_or("");
+        message.highlights = self.highlights.find(channel, &message.text);
+    }
+
+    /// メンション解決済みのメッセージに、マスク → NGワードの伏せ字 → ハイライトの順で表示用の加工をする。
+    /// NGワードを伏せ字にした場合は true を返す
+    fn apply_display_transforms(&self, message: &mut SlackMessage) -> bool {
+        self.apply_redaction(message);
+        let unmasked = message.text.clone();
+        self.apply_ng_words(message);
+        let masked = message.text != unmasked;
+        self.apply_highlights(message);
+        masked
+    }
+}
+
+impl SlackClientState {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(SlackClientInner {
+                config: SlackConfig::default(),
+                is_connected: false,
+                watched_channels: std::collections::HashSet::new(),
+                user_cache: HashMap::new(),
+                custom_emoji_cache: HashMap::new(),
+                current_channel_name: "waigaya".to_string(),
+                socket_cancel: None,
+                socket_task: None,
+                socket_generation: 0,
+                last_event_at: None,
+                parent_message_cache: ParentMessageCache::default(),
+                reaction_cache: ReactionCache::default(),
+                filter_stats: FilterStats::default(),
+                filters: FilterSet::default(),
+                highlights: HighlightSet::default(),
+                ng_words: NgWordDictionary::default(),
+                anonymizer: Anonymizer::default(),
+                burst_governor: BurstGovernor::default(),
+                pause: PauseBuffer::default(),
+                schedule_state: ScheduleState::default(),
+                recorder: Arc::new(std::sync::Mutex::new(EventRecorder::default())),
+                replay_cancel: None,
+                replay_generation: 0,
+                replay_state: ReplayState::default(),
+                channel_name_cache: HashMap::new(),
+                bot_user_id: None,
+            })),
+        }
+    }
+
+    // --- 設定管理 ---
+
+    /// 保存済みの設定をそのまま反映する（起動時）
+    pub async fn restore_config(&self, config: SlackConfig) {
+        let mut inner = self.inner.write().await;
+        log::info!("監視チャンネルを復元: {:?}", config.channels);
+        inner.watched_channels = config.channels.iter().cloned().collect();
+        inner.filters = FilterSet::new(&config.filter_rules);
+        inner.highlights = HighlightSet::new(&config.highlight_rules);
+        inner.config = config;
+    }
+
+    /// 設定画面から送られた設定を反映する
+    ///
+    /// 監視チャンネル・スレッド・各種ルールなどはそれぞれのコマンドでのみ変更するため、
+    /// 画面側が古い値を持っていても上書きしないようここでは受け取らない
+    pub async fn update_config(&self, config: SlackConfig) {
+        let mut inner = self.inner.write().await;
+        inner.config.bot_token = config.bot_token;
+        inner.config.app_token = config.app_token;
+        inner.config.thread_parent_max_chars = config.thread_parent_max_chars;
+        inner.config.trending_threshold = config.trending_threshold;
+        inner.config.max_messages_per_minute = config.max_messages_per_minute;
+    }
+
+    /// バックエンド側で追加された設定も含めた現在の設定
+    pub async fn get_config(&self) -> SlackConfig {
+        self.inner.read().await.config.clone()
+    }
+
+    pub async fn get_current_channel_name(&self) -> String {
+        self.inner.read().await.current_channel_name.clone()
+    }
+
+    // --- 接続管理 ---
+
+    pub async fn test_connection(&self) -> SlackConnectionResult {
+        let inner = self.inner.read().await;
+        let bot_token = inner.config.bot_token.clone();
+        let app_token = inner.config.app_token.clone();
+        drop(inner);
+
+        if bot_token.is_empty() || app_token.is_empty() {
+            return SlackConnectionResult {
+                success: false,
+                error: Some("Bot Token と App Token が必要です".to_string()),
+            };
+        }
+
+        // Bot Token テスト
+        match self.auth_test(&bot_token).await {
+            Ok(resp) => {
+                if !resp.ok {
+                    return SlackConnectionResult {
+                        success: false,
+                        error: Some(format!("Bot Token認証失敗: {}", resp.error.unwrap_or_default())),
+                    };
+                }
+                log::info!("Bot Token認証成功: {:?}", resp.user);
             }
             Err(e) => {
                 return SlackConnectionResult {
@@ -509,6 +1418,7 @@ impl SlackClientState {
                     };
                 }
                 log::info!("Bot Token認証成功");
+                self.inner.write().await.bot_user_id = resp.user_id;
             }
             Err(e) => {
                 return SlackConnectionResult {
@@ -637,7 +1547,7 @@ impl SlackClientState {
 
         loop {
             let mut params = vec![
-                ("types", "public_channel,private_channel"),
+                ("types", "public_channel,private_channel,mpim,im"),
                 ("exclude_archived", "true"),
                 ("limit", "1000"),
             ];
@@ -682,12 +1592,7 @@ impl SlackClientState {
             }
 
             for ch in result.channels {
-                all_channels.push(SlackChannel {
-                    id: ch.id,
-                    name: ch.name,
-                    is_private: Some(ch.is_private),
-                    is_member: Some(ch.is_member),
-                });
+                all_channels.push(Self::to_slack_channel(&bot_token, ch, &self.inner).await);
             }
 
             match result.response_metadata {
@@ -711,19 +1616,112 @@ impl SlackClientState {
         let bot_token = inner.config.bot_token.clone();
         drop(inner);
 
-        if bot_token.is_empty() {
-            return SlackChannel {
+        Self::fetch_channel_info_static(&bot_token, channel_id, &self.inner)
+            .await
+            .unwrap_or_else(|| SlackChannel {
                 id: channel_id.to_string(),
                 name: "unknown".to_string(),
                 is_private: None,
                 is_member: None,
+                channel_type: None,
+                is_archived: None,
+            })
+    }
+
+    /// Slack API のチャンネル情報を SlackChannel に変換する。
+    /// DM は相手のユーザー名、グループDMは参加者名の一覧を表示名とする（匿名モードでは仮名）。
+    async fn to_slack_channel(
+        bot_token: &str,
+        ch: ConversationChannel,
+        inner: &Arc<RwLock<SlackClientInner>>,
+    ) -> SlackChannel {
+        if ch.is_im {
+            let name = match ch.user.as_deref() {
+                Some(user_id) => {
+                    let user_info = Self::fetch_user_info_static(bot_token, user_id, inner).await;
+                    inner.write().await.present_user(user_id, &user_info).0
+                }
+                None => ch.id.clone(),
+            };
+            return SlackChannel {
+                id: ch.id,
+                name,
+                is_private: Some(true),
+                is_member: Some(true),
+                channel_type: Some("im".to_string()),
+                is_archived: None,
             };
         }
 
+        if ch.is_mpim {
+            let name = Self::fetch_mpim_label(bot_token, &ch.id, inner)
+                .await
+                .unwrap_or(ch.name);
+            return SlackChannel {
+                id: ch.id,
+                name,
+                is_private: Some(true),
+                is_member: Some(true),
+                channel_type: Some("mpim".to_string()),
+                is_archived: None,
+            };
+        }
+
+        SlackChannel {
+            id: ch.id,
+            name: ch.name,
+            is_private: Some(ch.is_private),
+            is_member: Some(ch.is_member),
+            channel_type: None,
+            is_archived: Some(ch.is_archived).filter(|archived| *archived),
+        }
+    }
+
+    /// グループDMの参加者名（ボット自身を除く、匿名モードでは仮名）をカンマ区切りで返す
+    async fn fetch_mpim_label(
+        bot_token: &str,
+        channel_id: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+    ) -> Option<String> {
+        let resp = http_client()
+            .get("https://slack.com/api/conversations.members")
+            .bearer_auth(bot_token)
+            .query(&[("channel", channel_id)])
+            .send()
+            .await
+            .ok()?;
+        let result: ConversationsMembersResponse = resp.json().await.ok()?;
+        if !result.ok {
+            return None;
+        }
+
+        let bot_user_id = inner.read().await.bot_user_id.clone();
+        let mut names = Vec::new();
+        for member in result.members.iter().filter(|m| Some(m.as_str()) != bot_user_id.as_deref()) {
+            let user_info = Self::fetch_user_info_static(bot_token, member, inner).await;
+            names.push(inner.write().await.present_user(member, &user_info).0);
+        }
+        if names.is_empty() {
+            None
+        } else {
+            Some(names.join(", "))
+        }
+    }
+
+    /// チャンネル情報を取得（static版、Socket Modeタスク内でも使用）
+    async fn fetch_channel_info_static(
+        bot_token: &str,
+        channel_id: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+    ) -> Option<SlackChannel> {
+        if bot_token.is_empty() {
+            return None;
+        }
+
         let client = http_client();
         let resp = client
             .get("https://slack.com/api/conversations.info")
-            .bearer_auth(&bot_token)
+            .bearer_auth(bot_token)
             .query(&[("channel", channel_id)])
             .send()
             .await;
@@ -732,12 +1730,7 @@ impl SlackClientState {
             Ok(r) => {
                 if let Ok(result) = r.json::<ConversationsInfoResponse>().await {
                     if let Some(ch) = result.channel {
-                        return SlackChannel {
-                            id: ch.id,
-                            name: ch.name,
-                            is_private: Some(ch.is_private),
-                            is_member: Some(ch.is_member),
-                        };
+                        return Some(Self::to_slack_channel(bot_token, ch, inner).await);
                     }
                 }
             }
@@ -746,15 +1739,46 @@ impl SlackClientState {
             }
         }
 
-        SlackChannel {
-            id: channel_id.to_string(),
-            name: "unknown".to_string(),
-            is_private: None,
-            is_member: None,
+        None
+    }
+
+    /// conversations.join でボットをパブリックチャンネルに参加させる（channels:join スコープが必要）
+    async fn join_channel_static(bot_token: &str, channel_id: &str) -> Result<(), String> {
+        let client = http_client();
+        let resp = client
+            .post("https://slack.com/api/conversations.join")
+            .bearer_auth(bot_token)
+            .form(&[("channel", channel_id)])
+            .send()
+            .await
+            .map_err(|e| format!("チャンネル参加エラー: {}", e))?;
+
+        let result: ConversationsJoinResponse = resp
+            .json()
+            .await
+            .map_err(|e| format!("レスポンス解析エラー: {}", e))?;
+
+        if result.ok {
+            log::info!("チャンネルに参加しました: {}", channel_id);
+            return Ok(());
+        }
+
+        match result.error.as_deref().unwrap_or("unknown") {
+            "missing_scope" => Err(format!(
+                "チャンネルへの自動参加には {} スコープが必要です。Slack App の [OAuth & Permissions] で Bot Token Scopes に追加し、アプリを再インストールしてください。",
+                result.needed.as_deref().unwrap_or("channels:join")
+            )),
+            "method_not_supported_for_channel_type" => {
+                Err("プライベートチャンネルには自動参加できません。Slack で /invite してください。".to_string())
+            }
+            "is_archived" => Err("アーカイブ済みのチャンネルには参加できません".to_string()),
+            code => Err(format!("チャンネル参加に失敗しました: {}", code)),
         }
     }
 
-    pub async fn add_watch_channel(&self, channel_id: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+    /// チャンネルを監視に追加する。`auto_join` が true の場合、ボット未参加のパブリックチャンネルには
+    /// conversations.join で参加してから追加する。
+    pub async fn add_watch_channel(&self, channel_id: &str, auto_join: bool, storage: &crate::storage::StorageState) -> ChannelActionResult {
         log::info!("add_watch_channel 開始: {}", channel_id);
         {
             let inner = self.inner.read().await;
@@ -770,8 +1794,24 @@ impl SlackClientState {
         }
 
         log::info!("チャンネル情報を取得中: {}", channel_id);
-        let channel_info = self.get_channel_info(channel_id).await;
+        let mut channel_info = self.get_channel_info(channel_id).await;
         log::info!("チャンネル情報: name={}, is_member={:?}", channel_info.name, channel_info.is_member);
+        let mut joined = false;
+        if channel_info.is_member == Some(false) && auto_join && channel_info.is_private != Some(true) {
+            log::info!("ボット未参加のためチャンネルに参加します: {}", channel_info.name);
+            let bot_token = self.inner.read().await.config.bot_token.clone();
+            if let Err(e) = Self::join_channel_static(&bot_token, channel_id).await {
+                log::warn!("チャンネル参加失敗: {} {}", channel_info.name, e);
+                return ChannelActionResult {
+                    success: false,
+                    error: Some(e),
+                    message: None,
+                };
+            }
+            // 参加後のメンバー状態を再確認
+            channel_info = self.get_channel_info(channel_id).await;
+            joined = true;
+        }
         if channel_info.is_member == Some(false) {
             log::warn!("ボットがチャンネルに参加していません: {}", channel_info.name);
             return ChannelActionResult {
@@ -781,17 +1821,7 @@ impl SlackClientState {
             };
         }
 
-        {
-            let mut inner = self.inner.write().await;
-            inner.watched_channels.insert(channel_id.to_string());
-            inner.config.channels = inner.watched_channels.iter().cloned().collect();
-            inner.config.watched_channel_data.insert(channel_id.to_string(), channel_info.clone());
-
-            // 最初のチャンネルならチャンネル名を更新
-            if inner.watched_channels.len() == 1 {
-                inner.current_channel_name = channel_info.name.clone();
-            }
-        }
+        self.inner.write().await.insert_watched_channel(channel_info.clone());
 
         log::info!("チャンネル監視追加完了: {}", channel_id);
 
@@ -801,7 +1831,11 @@ impl SlackClientState {
         ChannelActionResult {
             success: true,
             error: None,
-            message: Some(format!("#{} を監視対象に追加しました", channel_info.name)),
+            message: Some(if joined {
+                format!("#{} に参加し、監視対象に追加しました", channel_info.name)
+            } else {
+                format!("#{} を監視対象に追加しました", channel_info.name)
+            }),
         }
     }
 
@@ -843,19 +1877,1033 @@ impl SlackClientState {
         }
     }
 
-    async fn save_channel_settings(&self, storage: &crate::storage::StorageState) {
-        let inner = self.inner.read().await;
-        let config = inner.config.clone();
-        drop(inner);
+    /// 監視チャンネルごとに conversations.info を呼び、存在・アーカイブ・ボットの参加状況を確認する
+    pub async fn validate_watched_channels(&self) -> ChannelValidationResult {
+        let (bot_token, channels) = {
+            let inner = self.inner.read().await;
+            let mut ids: Vec<String> = inner.watched_channels.iter().cloned().collect();
+            ids.sort();
+            let channels: Vec<(String, String)> = ids
+                .into_iter()
+                .map(|id| {
+                    let name = inner
+                        .config
+                        .watched_channel_data
+                        .get(&id)
+                        .map(|c| c.name.clone())
+                        .unwrap_or_else(|| id.clone());
+                    (id, name)
+                })
+                .collect();
+            (inner.config.bot_token.clone(), channels)
+        };
 
-        if let Err(e) = storage.save_config(&config) {
-            log::error!("チャンネル設定保存失敗: {}", e);
+        if bot_token.is_empty() {
+            return ChannelValidationResult {
+                success: false,
+                channels: Vec::new(),
+                error: Some("Bot Tokenが設定されていません".to_string()),
+            };
         }
-    }
-
-    // --- ユーザー管理 ---
 
-    pub async fn fetch_all_users(&self) -> Result<(usize, serde_json::Value), String> {
+        // レート制限を避けるため 1 チャンネルずつ確認する
+        let client = http_client();
+        let mut report = Vec::with_capacity(channels.len());
+        for (channel_id, name) in channels {
+            let resp = client
+                .get("https://slack.com/api/conversations.info")
+                .bearer_auth(&bot_token)
+                .query(&[("channel", channel_id.as_str())])
+                .send()
+                .await;
+
+            let (status, error) = match resp {
+                Ok(r) => match r.json::<ConversationsInfoResponse>().await {
+                    Ok(info) => Self::channel_health_status(&info),
+                    Err(e) => (ChannelHealthStatus::Unknown, Some(format!("レスポンス解析エラー: {}", e))),
+                },
+                Err(e) => (ChannelHealthStatus::Unknown, Some(format!("チャンネル情報取得エラー: {}", e))),
+            };
+
+            if status != ChannelHealthStatus::Ok {
+                log::warn!("監視チャンネル {} ({}) の状態: {:?}", name, channel_id, status);
+            }
+            report.push(ChannelHealth {
+                channel_id,
+                name,
+                status,
+                error,
+            });
+        }
+
+        ChannelValidationResult {
+            success: true,
+            channels: report,
+            error: None,
+        }
+    }
+
+    fn channel_health_status(info: &ConversationsInfoResponse) -> (ChannelHealthStatus, Option<String>) {
+        if !info.ok {
+            return match info.error.as_deref() {
+                Some("channel_not_found") => (ChannelHealthStatus::NotFound, None),
+                other => (ChannelHealthStatus::Unknown, Some(other.unwrap_or("unknown").to_string())),
+            };
+        }
+        match &info.channel {
+            None => (ChannelHealthStatus::NotFound, None),
+            Some(ch) if ch.is_archived => (ChannelHealthStatus::Archived, None),
+            // DM は is_member を返さないため参加済みとみなす
+            Some(ch) if !ch.is_member && !ch.is_im => (ChannelHealthStatus::NotMember, None),
+            Some(_) => (ChannelHealthStatus::Ok, None),
+        }
+    }
+
+    // --- スレッド監視 ---
+
+    pub async fn add_watch_thread(&self, channel_id: &str, thread_ts: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        let thread = WatchedThread {
+            channel: channel_id.to_string(),
+            thread_ts: thread_ts.to_string(),
+        };
+        {
+            let mut inner = self.inner.write().await;
+            if inner.config.watched_threads.contains(&thread) {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("指定されたスレッドは既に監視されています".to_string()),
+                    message: None,
+                };
+            }
+            inner.config.watched_threads.push(thread);
+        }
+
+        log::info!("スレッド監視追加: {} {}", channel_id, thread_ts);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("スレッドを監視対象に追加しました".to_string()),
+        }
+    }
+
+    pub async fn remove_watch_thread(&self, channel_id: &str, thread_ts: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        {
+            let mut inner = self.inner.write().await;
+            let before = inner.config.watched_threads.len();
+            inner
+                .config
+                .watched_threads
+                .retain(|t| !(t.channel == channel_id && t.thread_ts == thread_ts));
+            if inner.config.watched_threads.len() == before {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("指定されたスレッドは監視されていません".to_string()),
+                    message: None,
+                };
+            }
+        }
+
+        log::info!("スレッド監視削除: {} {}", channel_id, thread_ts);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("スレッドの監視を解除しました".to_string()),
+        }
+    }
+
+    pub async fn get_watched_threads(&self) -> Vec<WatchedThread> {
+        self.inner.read().await.config.watched_threads.clone()
+    }
+
+    // --- ユーザーフォロー ---
+
+    pub async fn follow_user(&self, user_id: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        {
+            let mut inner = self.inner.write().await;
+            if inner.config.followed_users.iter().any(|u| u == user_id) {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("指定されたユーザーは既にフォローしています".to_string()),
+                    message: None,
+                };
+            }
+            inner.config.followed_users.push(user_id.to_string());
+        }
+
+        log::info!("ユーザーフォロー追加: {}", user_id);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("ユーザーをフォローしました".to_string()),
+        }
+    }
+
+    pub async fn unfollow_user(&self, user_id: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        {
+            let mut inner = self.inner.write().await;
+            let before = inner.config.followed_users.len();
+            inner.config.followed_users.retain(|u| u != user_id);
+            if inner.config.followed_users.len() == before {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("指定されたユーザーはフォローしていません".to_string()),
+                    message: None,
+                };
+            }
+        }
+
+        log::info!("ユーザーフォロー解除: {}", user_id);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("フォローを解除しました".to_string()),
+        }
+    }
+
+    pub async fn get_followed_users(&self) -> Vec<String> {
+        self.inner.read().await.config.followed_users.clone()
+    }
+
+    // --- チャンネルごとの設定 ---
+
+    pub async fn get_channel_settings(&self, channel_id: &str) -> ChannelSettings {
+        self.inner
+            .read()
+            .await
+            .config
+            .channel_settings
+            .get(channel_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub async fn set_channel_settings(&self, channel_id: &str, settings: ChannelSettings, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        if let Some(Err(e)) = settings.redaction.as_ref().map(RedactionSettings::validate) {
+            return ChannelActionResult {
+                success: false,
+                error: Some(e),
+                message: None,
+            };
+        }
+        {
+            let mut inner = self.inner.write().await;
+            // 既定値と同じ設定は保持しない
+            if settings == ChannelSettings::default() {
+                inner.config.channel_settings.remove(channel_id);
+            } else {
+                inner.config.channel_settings.insert(channel_id.to_string(), settings);
+            }
+        }
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("チャンネル設定を保存しました".to_string()),
+        }
+    }
+
+    // --- メッセージフィルタ ---
+
+    pub async fn add_filter_rule(&self, mut rule: FilterRule, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        if let Err(e) = rule.validate() {
+            return ChannelActionResult {
+                success: false,
+                error: Some(e),
+                message: None,
+            };
+        }
+        if rule.id.is_empty() {
+            let millis = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            rule.id = format!("filter-{}", millis);
+        }
+
+        {
+            let mut inner = self.inner.write().await;
+            if inner.config.filter_rules.iter().any(|r| r.id == rule.id) {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("同じIDのフィルタが既に登録されています".to_string()),
+                    message: None,
+                };
+            }
+            inner.config.filter_rules.push(rule.clone());
+            inner.filters = FilterSet::new(&inner.config.filter_rules);
+        }
+
+        log::info!("フィルタルール追加: {:?}", rule);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some(rule.id),
+        }
+    }
+
+    pub async fn remove_filter_rule(&self, id: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        {
+            let mut inner = self.inner.write().await;
+            let before = inner.config.filter_rules.len();
+            inner.config.filter_rules.retain(|r| r.id != id);
+            if inner.config.filter_rules.len() == before {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("指定されたフィルタは登録されていません".to_string()),
+                    message: None,
+                };
+            }
+            inner.filters = FilterSet::new(&inner.config.filter_rules);
+        }
+
+        log::info!("フィルタルール削除: {}", id);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("フィルタを削除しました".to_string()),
+        }
+    }
+
+    pub async fn get_filter_rules(&self) -> Vec<FilterRule> {
+        self.inner.read().await.config.filter_rules.clone()
+    }
+
+    /// 保存前のルールがサンプルテキストに一致するか確認する
+    pub fn test_filter_rule(&self, rule: &FilterRule, text: &str, user_id: Option<&str>) -> FilterTestResult {
+        match rule.validate().and_then(|_| rule.matches(text, user_id)) {
+            Ok(matched) => FilterTestResult {
+                success: true,
+                matched,
+                error: None,
+            },
+            Err(e) => FilterTestResult {
+                success: false,
+                matched: false,
+                error: Some(e),
+            },
+        }
+    }
+
+    pub async fn get_filter_stats(&self) -> FilterStats {
+        self.inner.read().await.filter_stats.clone()
+    }
+
+    // --- マスク ---
+
+    pub async fn get_redaction_settings(&self) -> RedactionSettings {
+        self.inner.read().await.config.redaction.clone().unwrap_or_default()
+    }
+
+    pub async fn set_redaction_settings(&self, settings: RedactionSettings, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        if let Err(e) = settings.validate() {
+            return ChannelActionResult {
+                success: false,
+                error: Some(e),
+                message: None,
+            };
+        }
+        log::info!("マスク設定更新: {:?}", settings);
+        self.inner.write().await.config.redaction = Some(settings);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("マスク設定を保存しました".to_string()),
+        }
+    }
+
+    // --- 一時停止 ---
+
+    pub async fn get_pause_state(&self) -> PauseState {
+        self.inner.read().await.pause.state()
+    }
+
+    /// 接続を維持したままキューへの送信を止める
+    pub async fn pause_stream(&self, app_handle: &tauri::AppHandle) -> PauseState {
+        Self::pause_held(&self.inner, PauseSource::Manual, app_handle).await
+    }
+
+    /// 送信を再開し、溜めていたメッセージを指定の方法で流す
+    pub async fn resume_stream(&self, mode: ResumeMode, app_handle: &tauri::AppHandle) -> PauseState {
+        Self::resume_held(&self.inner, PauseSource::Manual, mode, app_handle).await
+    }
+
+    async fn pause_held(
+        inner: &Arc<RwLock<SlackClientInner>>,
+        source: PauseSource,
+        app_handle: &tauri::AppHandle,
+    ) -> PauseState {
+        let state = {
+            let mut inner = inner.write().await;
+            inner.pause.pause(source);
+            inner.pause.state()
+        };
+        log::info!("メッセージの表示を一時停止: {:?}", source);
+        let _ = app_handle.emit("stream-pause-state", &state);
+        state
+    }
+
+    /// 一時停止を解除する。スケジュールからの再開では、スケジュールが止めた場合だけ再開し手動の一時停止は残す
+    async fn resume_held(
+        inner: &Arc<RwLock<SlackClientInner>>,
+        source: PauseSource,
+        mode: ResumeMode,
+        app_handle: &tauri::AppHandle,
+    ) -> PauseState {
+        let (resumed, state) = {
+            let mut inner = inner.write().await;
+            let resumed = inner.pause.resume(source);
+            (resumed, inner.pause.state())
+        };
+        let Some((held, dropped)) = resumed else {
+            if state.paused {
+                log::info!("手動の一時停止中のため表示を再開しません");
+                let _ = app_handle.emit("stream-pause-state", &state);
+            }
+            return state;
+        };
+        log::info!("メッセージの表示を再開: {:?} 保持{}件 破棄{}件", mode, held.len(), dropped);
+
+        let replay = match mode {
+            ResumeMode::All => held,
+            ResumeMode::Summary => summarize_held_messages(&held),
+            ResumeMode::Discard => Vec::new(),
+        };
+        for message in replay {
+            if let Err(e) = Self::enqueue(app_handle, message).await {
+                log::error!("メッセージ送信エラー: {}", e);
+            }
+        }
+        let _ = app_handle.emit("stream-pause-state", &state);
+        state
+    }
+
+    // --- スケジュール ---
+
+    pub async fn get_schedule(&self) -> ScheduleSettings {
+        self.inner.read().await.config.schedule.clone().unwrap_or_default()
+    }
+
+    pub async fn get_schedule_state(&self) -> ScheduleState {
+        self.inner.read().await.schedule_state.clone()
+    }
+
+    pub async fn set_schedule(
+        &self,
+        settings: ScheduleSettings,
+        storage: &crate::storage::StorageState,
+        app_handle: &tauri::AppHandle,
+    ) -> ChannelActionResult {
+        if let Err(e) = settings.validate() {
+            return ChannelActionResult {
+                success: false,
+                error: Some(e),
+                message: None,
+            };
+        }
+        log::info!("スケジュール更新: {:?}", settings);
+        self.inner.write().await.config.schedule = Some(settings);
+        self.save_channel_settings(storage).await;
+        Self::apply_schedule(&self.inner, app_handle).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("スケジュールを保存しました".to_string()),
+        }
+    }
+
+    /// 履歴の保存設定を設定ファイルに反映する（保持期間の適用は HistoryState 側で行う）
+    pub async fn set_history_settings(&self, settings: HistorySettings, storage: &crate::storage::StorageState) {
+        self.inner.write().await.config.history = Some(settings);
+        self.save_channel_settings(storage).await;
+    }
+
+    /// 書き出す履歴の本文に、現在のマスク設定とNGワードの伏せ字を適用する
+    pub async fn mask_history_entries(&self, entries: &mut [HistoryEntry]) {
+        let inner = self.inner.read().await;
+        for entry in entries.iter_mut() {
+            if let Some(settings) = inner.redaction_settings(entry.channel.as_deref().unwrap_or("")) {
+                let (text, count) = crate::redaction::redact(&entry.text, settings);
+                entry.text = text;
+                entry.redacted |= count > 0;
+            }
+            let masked = inner.ng_words.mask(&entry.text);
+            entry.masked |= masked != entry.text;
+            entry.text = masked;
+        }
+    }
+
+    /// Socket Mode の接続とは独立に、スケジュールを定期的に判定する（起動時に現在の状態を通知する）
+    pub fn start_schedule_timer(&self, app_handle: tauri::AppHandle) {
+        let inner = self.inner.clone();
+        tauri::async_runtime::spawn(async move {
+            if !Self::apply_schedule(&inner, &app_handle).await {
+                let state = inner.read().await.schedule_state.clone();
+                let _ = app_handle.emit("schedule-state", &state);
+            }
+            let mut interval = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                Self::apply_schedule(&inner, &app_handle).await;
+            }
+        });
+    }
+
+    /// 現在時刻でスケジュールを判定し、状態が変わったら一時停止・再開して schedule-state を通知する（変わったら true）
+    async fn apply_schedule(inner: &Arc<RwLock<SlackClientInner>>, app_handle: &tauri::AppHandle) -> bool {
+        let now = chrono::Local::now().naive_local();
+        let (state, resume_mode) = {
+            let mut w = inner.write().await;
+            let settings = w.config.schedule.clone().unwrap_or_default();
+            let state = settings.evaluate(now);
+            if state == w.schedule_state {
+                return false;
+            }
+            w.schedule_state = state.clone();
+            (state, settings.resume_mode)
+        };
+
+        log::info!("スケジュール状態が変化: {:?}", state);
+        let _ = app_handle.emit("schedule-state", &state);
+        if state.action == Some(QuietAction::Pause) {
+            Self::pause_held(inner, PauseSource::Schedule, app_handle).await;
+        } else {
+            Self::resume_held(inner, PauseSource::Schedule, resume_mode, app_handle).await;
+        }
+        true
+    }
+
+    // --- 流量制限 ---
+
+    pub async fn get_throttle_metrics(&self) -> ThrottleMetrics {
+        self.inner.read().await.burst_governor.metrics.clone()
+    }
+
+    // --- 匿名表示 ---
+
+    pub async fn get_anonymous_mode(&self) -> bool {
+        self.inner.read().await.is_anonymous()
+    }
+
+    pub async fn set_anonymous_mode(&self, enabled: bool, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        log::info!("匿名モード切り替え: {}", enabled);
+        {
+            let mut w = self.inner.write().await;
+            w.config.anonymous_mode = Some(enabled);
+            // DM・グループDMの名前を実名／仮名で取り直す
+            w.channel_name_cache.clear();
+        }
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some(if enabled { "匿名モードを有効にしました" } else { "匿名モードを無効にしました" }.to_string()),
+        }
+    }
+
+    // --- NGワード ---
+
+    /// 保存済みの辞書を読み込む
+    pub async fn set_ng_words(&self, entries: Vec<NgWordEntry>) {
+        log::info!("NGワード辞書を適用: {}件", entries.len());
+        self.inner.write().await.ng_words = NgWordDictionary::new(entries);
+    }
+
+    pub async fn get_ng_words(&self) -> Vec<NgWordEntry> {
+        self.inner.read().await.ng_words.entries().to_vec()
+    }
+
+    /// 辞書を置き換えてファイルに保存する
+    async fn replace_ng_words(&self, entries: Vec<NgWordEntry>, storage: &crate::storage::StorageState) -> Result<(), String> {
+        storage.save_ng_words(&entries)?;
+        self.inner.write().await.ng_words = NgWordDictionary::new(entries);
+        Ok(())
+    }
+
+    fn ng_word_result(result: Result<(), String>, message: String) -> ChannelActionResult {
+        match result {
+            Ok(()) => ChannelActionResult {
+                success: true,
+                error: None,
+                message: Some(message),
+            },
+            Err(e) => ChannelActionResult {
+                success: false,
+                error: Some(e),
+                message: None,
+            },
+        }
+    }
+
+    /// 語を追加する（既に登録済みなら動作だけ更新）
+    pub async fn add_ng_word(&self, word: &str, hide_message: bool, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        let word = word.trim();
+        if word.is_empty() {
+            return Self::ng_word_result(Err("NGワードを入力してください".to_string()), String::new());
+        }
+        let mut entries = self.get_ng_words().await;
+        match entries.iter_mut().find(|e| e.word == word) {
+            Some(entry) => entry.hide_message = hide_message,
+            None => entries.push(NgWordEntry {
+                word: word.to_string(),
+                hide_message,
+            }),
+        }
+        log::info!("NGワード追加: {} (hide={})", word, hide_message);
+        let result = self.replace_ng_words(entries, storage).await;
+        Self::ng_word_result(result, "NGワードを追加しました".to_string())
+    }
+
+    pub async fn remove_ng_word(&self, word: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        let mut entries = self.get_ng_words().await;
+        let before = entries.len();
+        entries.retain(|e| e.word != word);
+        if entries.len() == before {
+            return Self::ng_word_result(Err("指定されたNGワードは登録されていません".to_string()), String::new());
+        }
+        log::info!("NGワード削除: {}", word);
+        let result = self.replace_ng_words(entries, storage).await;
+        Self::ng_word_result(result, "NGワードを削除しました".to_string())
+    }
+
+    /// テキスト/CSV から取り込む。replace が false なら既存の辞書に追加（同じ語は上書き）
+    pub async fn import_ng_words(
+        &self,
+        content: &str,
+        format: NgWordFormat,
+        replace: bool,
+        storage: &crate::storage::StorageState,
+    ) -> ChannelActionResult {
+        let imported = match crate::ng_words::parse(content, format) {
+            Ok(imported) => imported,
+            Err(e) => return Self::ng_word_result(Err(e), String::new()),
+        };
+        let count = imported.len();
+        let mut entries = if replace { Vec::new() } else { self.get_ng_words().await };
+        for entry in imported {
+            match entries.iter_mut().find(|e| e.word == entry.word) {
+                Some(existing) => existing.hide_message = entry.hide_message,
+                None => entries.push(entry),
+            }
+        }
+        log::info!("NGワードを取り込み: {}件 (replace={})", count, replace);
+        let result = self.replace_ng_words(entries, storage).await;
+        Self::ng_word_result(result, format!("{}件のNGワードを取り込みました", count))
+    }
+
+    pub async fn export_ng_words(&self, format: NgWordFormat) -> String {
+        crate::ng_words::export(self.inner.read().await.ng_words.entries(), format)
+    }
+
+    // --- ハイライト ---
+
+    pub async fn add_highlight_rule(&self, mut rule: HighlightRule, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        if let Err(e) = rule.to_regex() {
+            return ChannelActionResult {
+                success: false,
+                error: Some(e),
+                message: None,
+            };
+        }
+        if rule.id.is_empty() {
+            let millis = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis();
+            rule.id = format!("highlight-{}", millis);
+        }
+
+        {
+            let mut inner = self.inner.write().await;
+            if inner.config.highlight_rules.iter().any(|r| r.id == rule.id) {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("同じIDのハイライトが既に登録されています".to_string()),
+                    message: None,
+                };
+            }
+            inner.config.highlight_rules.push(rule.clone());
+            inner.highlights = HighlightSet::new(&inner.config.highlight_rules);
+        }
+
+        log::info!("ハイライトルール追加: {:?}", rule);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some(rule.id),
+        }
+    }
+
+    pub async fn remove_highlight_rule(&self, id: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        {
+            let mut inner = self.inner.write().await;
+            let before = inner.config.highlight_rules.len();
+            inner.config.highlight_rules.retain(|r| r.id != id);
+            if inner.config.highlight_rules.len() == before {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("指定されたハイライトは登録されていません".to_string()),
+                    message: None,
+                };
+            }
+            inner.highlights = HighlightSet::new(&inner.config.highlight_rules);
+        }
+
+        log::info!("ハイライトルール削除: {}", id);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("ハイライトを削除しました".to_string()),
+        }
+    }
+
+    pub async fn get_highlight_rules(&self) -> Vec<HighlightRule> {
+        self.inner.read().await.config.highlight_rules.clone()
+    }
+
+    // --- チャンネルルール ---
+
+    pub async fn add_channel_rule(&self, rule: ChannelRule, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        if rule.pattern.trim().trim_start_matches('#').is_empty() {
+            return ChannelActionResult {
+                success: false,
+                error: Some("パターンを入力してください".to_string()),
+                message: None,
+            };
+        }
+        if let Err(e) = rule.to_regex() {
+            return ChannelActionResult {
+                success: false,
+                error: Some(e),
+                message: None,
+            };
+        }
+
+        {
+            let mut inner = self.inner.write().await;
+            if inner.config.channel_rules.iter().any(|r| r.pattern == rule.pattern) {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("同じパターンのルールが既に登録されています".to_string()),
+                    message: None,
+                };
+            }
+            inner.config.channel_rules.push(rule.clone());
+        }
+
+        log::info!("チャンネルルール追加: {:?}", rule);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some(format!("ルール「{}」を追加しました", rule.pattern)),
+        }
+    }
+
+    pub async fn remove_channel_rule(&self, pattern: &str, storage: &crate::storage::StorageState) -> ChannelActionResult {
+        {
+            let mut inner = self.inner.write().await;
+            let before = inner.config.channel_rules.len();
+            inner.config.channel_rules.retain(|r| r.pattern != pattern);
+            if inner.config.channel_rules.len() == before {
+                return ChannelActionResult {
+                    success: false,
+                    error: Some("指定されたルールは登録されていません".to_string()),
+                    message: None,
+                };
+            }
+        }
+
+        log::info!("チャンネルルール削除: {}", pattern);
+        self.save_channel_settings(storage).await;
+
+        ChannelActionResult {
+            success: true,
+            error: None,
+            message: Some("ルールを削除しました".to_string()),
+        }
+    }
+
+    pub async fn get_channel_rules(&self) -> Vec<ChannelRule> {
+        self.inner.read().await.config.channel_rules.clone()
+    }
+
+    /// チャンネル一覧をルールと照合し、一致したチャンネルを監視に追加する
+    pub async fn apply_channel_rules(&self, storage: &crate::storage::StorageState) -> ChannelRuleApplyResult {
+        let (bot_token, has_rules) = {
+            let inner = self.inner.read().await;
+            (inner.config.bot_token.clone(), !inner.config.channel_rules.is_empty())
+        };
+        if !has_rules {
+            return ChannelRuleApplyResult {
+                success: true,
+                added: Vec::new(),
+                error: None,
+            };
+        }
+
+        let list = self.get_channel_list().await;
+        let Some(channels) = list.channels else {
+            return ChannelRuleApplyResult {
+                success: false,
+                added: Vec::new(),
+                error: list.error,
+            };
+        };
+
+        let added = Self::apply_channel_rules_static(&bot_token, channels, &self.inner, storage).await;
+        ChannelRuleApplyResult {
+            success: true,
+            added,
+            error: None,
+        }
+    }
+
+    /// 未監視のチャンネルのうちルールに一致するものを監視に追加し、追加したチャンネルを返す。
+    /// ボットが未参加の場合、auto_join のルールならパブリックチャンネルに参加してから追加する。
+    async fn apply_channel_rules_static(
+        bot_token: &str,
+        channels: Vec<SlackChannel>,
+        inner: &Arc<RwLock<SlackClientInner>>,
+        storage: &crate::storage::StorageState,
+    ) -> Vec<SlackChannel> {
+        let rules: Vec<(ChannelRule, regex::Regex)> = {
+            let read = inner.read().await;
+            read.config
+                .channel_rules
+                .iter()
+                .filter_map(|rule| rule.to_regex().ok().map(|re| (rule.clone(), re)))
+                .collect()
+        };
+        if rules.is_empty() {
+            return Vec::new();
+        }
+
+        let mut added = Vec::new();
+        for mut channel in channels {
+            if channel.is_direct_message() || inner.read().await.watched_channels.contains(&channel.id) {
+                continue;
+            }
+            let Some((rule, _)) = rules.iter().find(|(_, re)| re.is_match(&channel.name)) else {
+                continue;
+            };
+
+            if channel.is_member == Some(false) {
+                if !rule.auto_join || channel.is_private == Some(true) || bot_token.is_empty() {
+                    log::info!("ルール一致だがボット未参加のためスキップ: #{}", channel.name);
+                    continue;
+                }
+                if let Err(e) = Self::join_channel_static(bot_token, &channel.id).await {
+                    log::warn!("ルール一致チャンネルへの参加失敗: #{} {}", channel.name, e);
+                    continue;
+                }
+                channel.is_member = Some(true);
+            }
+
+            log::info!("ルール「{}」に一致したチャンネルを監視に追加: #{}", rule.pattern, channel.name);
+            inner.write().await.insert_watched_channel(channel.clone());
+            added.push(channel);
+        }
+
+        if !added.is_empty() {
+            Self::persist_config(inner, storage).await;
+        }
+        added
+    }
+
+    async fn save_channel_settings(&self, storage: &crate::storage::StorageState) {
+        Self::persist_config(&self.inner, storage).await;
+    }
+
+    async fn persist_config(inner: &Arc<RwLock<SlackClientInner>>, storage: &crate::storage::StorageState) {
+        let config = inner.read().await.config.clone();
+
+        if let Err(e) = storage.save_config(&config) {
+            log::error!("チャンネル設定保存失敗: {}", e);
+        }
+    }
+
+    // --- スレッド ---
+
+    /// スレッド全体を conversations.replies でページングしながら取得し、
+    /// 表示用に名前・アイコン・メンション・画像を解決した SlackMessage として返す
+    pub async fn get_thread(&self, channel_id: &str, thread_ts: &str) -> ThreadResult {
+        let inner = self.inner.read().await;
+        let bot_token = inner.config.bot_token.clone();
+        drop(inner);
+
+        if bot_token.is_empty() {
+            return ThreadResult {
+                success: false,
+                messages: None,
+                error: Some("Bot Tokenが設定されていません".to_string()),
+            };
+        }
+
+        let client = http_client();
+        let mut replies = Vec::new();
+        let mut cursor = String::new();
+
+        loop {
+            let mut params = vec![
+                ("channel", channel_id),
+                ("ts", thread_ts),
+                ("limit", "200"),
+            ];
+            if !cursor.is_empty() {
+                params.push(("cursor", &cursor));
+            }
+
+            let resp = match client
+                .get("https://slack.com/api/conversations.replies")
+                .bearer_auth(&bot_token)
+                .query(&params)
+                .send()
+                .await
+            {
+                Ok(r) => r,
+                Err(e) => {
+                    return ThreadResult {
+                        success: false,
+                        messages: None,
+                        error: Some(format!("スレッド取得エラー: {}", e)),
+                    };
+                }
+            };
+
+            let result: ConversationsRepliesResponse = match resp.json().await {
+                Ok(r) => r,
+                Err(e) => {
+                    return ThreadResult {
+                        success: false,
+                        messages: None,
+                        error: Some(format!("レスポンス解析エラー: {}", e)),
+                    };
+                }
+            };
+
+            if !result.ok {
+                return ThreadResult {
+                    success: false,
+                    messages: None,
+                    error: Some(format!("APIエラー: {}", result.error.unwrap_or_default())),
+                };
+            }
+
+            replies.extend(result.messages);
+
+            match result.response_metadata {
+                Some(meta) if !meta.next_cursor.is_empty() => {
+                    cursor = meta.next_cursor;
+                }
+                _ => break,
+            }
+        }
+
+        let channel_name = Self::fetch_channel_name_static(&bot_token, channel_id, &self.inner).await;
+        let cached_channel = self.inner.read().await.config.watched_channel_data.get(channel_id).cloned();
+        let channel_type = match cached_channel {
+            Some(channel) => channel.channel_type,
+            None => Self::fetch_channel_info_static(&bot_token, channel_id, &self.inner)
+                .await
+                .and_then(|channel| channel.channel_type),
+        };
+        let mut messages = Vec::with_capacity(replies.len());
+        let mut image_fetches = Vec::new();
+        for reply in replies {
+            let ts = reply.ts.clone().unwrap_or_default();
+            let image_jobs = collect_image_jobs(reply.files.as_ref());
+
+            // 親メッセージはキャッシュにも登録しておく
+            if ts == thread_ts {
+                self.inner.write().await.parent_message_cache.insert(
+                    channel_id,
+                    thread_ts,
+                    ParentMessage {
+                        user_id: reply.user.clone().unwrap_or_default(),
+                        text: reply.text.clone().unwrap_or_default(),
+                        image_jobs: image_jobs.clone(),
+                        images: None,
+                    },
+                );
+            }
+
+            let user_id = reply.user.unwrap_or_default();
+            let user_info = Self::fetch_user_info_static(&bot_token, &user_id, &self.inner).await;
+            let text = Self::resolve_mentions(&reply.text.unwrap_or_default(), &bot_token, &self.inner).await;
+            let (user_name, user_icon) = self.inner.write().await.present_user(&user_id, &user_info);
+
+            image_fetches.push(Self::fetch_images(&bot_token, image_jobs));
+            messages.push(SlackMessage {
+                text,
+                user: user_name,
+                user_id: Some(user_id).filter(|id| !id.is_empty()),
+                user_icon,
+                channel: Some(channel_id.to_string()),
+                timestamp: Some(ts),
+                queue_action: None,
+                thread_ts: reply.thread_ts.or_else(|| Some(thread_ts.to_string())),
+                reply_to_user: None,
+                reply_to_text: None,
+                reply_to_user_icon: None,
+                reply_to_images: None,
+                images: None,
+                channel_name: channel_name.clone(),
+                channel_type: channel_type.clone(),
+                trending: false,
+                highlights: Vec::new(),
+                redacted: false,
+                attachments: Vec::new(),
+            });
+        }
+
+        // 画像はメッセージごとに並行して取得
+        let images = futures_util::future::join_all(image_fetches).await;
+        for (message, images) in messages.iter_mut().zip(images) {
+            message.images = images.filter(|images| !images.is_empty());
+        }
+        // 受信時と同じく、フィルタ・NGワードで表示しないものを除いてから表示用に加工する
+        {
+            let read = self.inner.read().await;
+            messages.retain(|message| !read.hides_fetched(channel_id, message.user_id.as_deref(), &message.text));
+            for message in messages.iter_mut() {
+                read.apply_display_transforms(message);
+            }
+        }
+
+        log::info!("スレッド取得完了: {} {} ({}件)", channel_id, thread_ts, messages.len());
+        ThreadResult {
+            success: true,
+            messages: Some(messages),
+            error: None,
+        }
+    }
+
+    // --- ユーザー管理 ---
+
+    pub async fn fetch_all_users(&self) -> Result<(usize, serde_json::Value), String> {
         let inner = self.inner.read().await;
         let bot_token = inner.config.bot_token.clone();
         drop(inner);
@@ -1278,6 +3326,8 @@ impl SlackClientState {
             let (mut write, mut read) = ws_stream.split();
             let mut health_interval = tokio::time::interval(tokio::time::Duration::from_secs(300));
             health_interval.tick().await;
+            let mut digest_interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
+            digest_interval.tick().await;
 
             let mut exit_reason = SocketInnerExit::Disconnected;
 
@@ -1294,6 +3344,9 @@ impl SlackClientState {
                         }
                     }
                 }
+                _ = digest_interval.tick() => {
+                    Self::flush_digests(&bot_token, &inner, &app_handle).await;
+                }
                 _ = cancel_rx.changed() => {
                     if *cancel_rx.borrow() {
                         log::info!("Socket Mode接続をキャンセル");
@@ -1310,330 +3363,1002 @@ impl SlackClientState {
                                 // ACK を送信
                                 if let Some(ref envelope_id) = socket_msg.envelope_id {
                                     let ack = serde_json::json!({"envelope_id": envelope_id});
-                                    if let Err(e) = write.send(Message::Text(ack.to_string().into())).await {
+                                    if let Err(e) = write.send(Message::Text(ack.to_string())).await {
                                         log::error!("ACK送信エラー: {}", e);
                                     }
                                 }
 
-                                // 診断: 受信したSocket Modeメッセージタイプをログ
-                                let msg_type_str = socket_msg.msg_type.as_deref().unwrap_or("unknown");
-                                log::info!("Socket Mode受信: type={}", msg_type_str);
-                                // hello は socket-mode-connected で通知済みのため emit 不要
-                                if msg_type_str != "hello" {
-                                    let _ = app_handle.emit("socket-mode-debug", format!("受信: type={}", msg_type_str));
-                                }
+                                // 診断: 受信したSocket Modeメッセージタイプをログ
+                                let msg_type_str = socket_msg.msg_type.as_deref().unwrap_or("unknown");
+                                let mut decision = msg_type_str.to_string();
+                                log::info!("Socket Mode受信: type={}", msg_type_str);
+                                // hello は socket-mode-connected で通知済みのため emit 不要
+                                if msg_type_str != "hello" {
+                                    let _ = app_handle.emit("socket-mode-debug", format!("受信: type={}", msg_type_str));
+                                }
+
+                                if socket_msg.msg_type.as_deref() == Some("disconnect") {
+                                    Self::record_event(&inner, &text, &decision).await;
+                                    let reason = socket_msg.reason.as_deref().unwrap_or("unknown");
+                                    log::info!("Socket Mode disconnect: reason={}", reason);
+                                    match reason {
+                                        "link_disabled" => {
+                                            exit_reason = SocketInnerExit::LinkDisabled;
+                                            let _ = app_handle.emit(
+                                                "socket-mode-error",
+                                                "App Tokenが無効化されました（link_disabled）。トークンを再生成してください。",
+                                            );
+                                            break 'inner;
+                                        }
+                                        "warning" | "refresh_requested" => {
+                                            break 'inner;
+                                        }
+                                        _ => break 'inner,
+                                    }
+                                }
+
+                                // events_api のメッセージイベントのみ処理
+                                if socket_msg.msg_type.as_deref() == Some("events_api") {
+                                    if let Some(event) = socket_msg.payload.as_ref().and_then(|p| p.event.as_ref()) {
+                                        decision = Self::handle_event(inner.clone(), app_handle.clone(), bot_token.clone(), event).await;
+                                    }
+                                }
+                                Self::record_event(&inner, &text, &decision).await;
+                            }
+                        }
+                        Some(Ok(Message::Ping(data))) => {
+                            if let Err(e) = write.send(Message::Pong(data)).await {
+                                log::error!("Pong送信エラー: {}", e);
+                            }
+                        }
+                        Some(Ok(Message::Close(_))) => {
+                            log::info!("Socket Mode WebSocket接続クローズ");
+                            break 'inner;
+                        }
+                        Some(Err(e)) => {
+                            log::error!("WebSocket受信エラー: {}", e);
+                            break 'inner;
+                        }
+                        None => {
+                            log::info!("WebSocketストリーム終了");
+                            break 'inner;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            }
+
+            inner.write().await.is_connected = false;
+            if !matches!(exit_reason, SocketInnerExit::Cancelled) {
+                let _ = app_handle.emit("socket-mode-disconnected", ());
+            }
+
+            match exit_reason {
+                SocketInnerExit::Cancelled | SocketInnerExit::LinkDisabled => {
+                    break 'reconnect;
+                }
+                SocketInnerExit::Disconnected => {
+                    if *cancel_rx.borrow() {
+                        break 'reconnect;
+                    }
+                    if Self::backoff_wait(&mut cancel_rx, backoff_secs).await {
+                        break 'reconnect;
+                    }
+                    backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF_SECS);
+                    continue 'reconnect;
+                }
+            }
+        }
+
+        {
+            let mut inner_guard = inner.write().await;
+            inner_guard.is_connected = false;
+            if inner_guard.socket_generation == generation {
+                inner_guard.socket_cancel = None;
+                inner_guard.socket_task = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// 記録が有効なら受信したエンベロープと処理結果を追記する
+    async fn record_event(inner: &Arc<RwLock<SlackClientInner>>, raw: &str, decision: &str) {
+        let recorder = inner.read().await.recorder.clone();
+        if !recorder.lock().is_ok_and(|r| r.is_enabled()) {
+            return;
+        }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let (raw, decision) = (raw.to_string(), decision.to_string());
+        // ファイルへの追記・ローテーションは非同期ランタイムの外で行う（受信順は待ち合わせで保つ）
+        let result = tokio::task::spawn_blocking(move || {
+            Self::lock_recorder(&recorder)?.record(&raw, &decision, now)
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+        if let Err(e) = result {
+            log::warn!("イベント記録エラー: {}", e);
+        }
+    }
+
+    fn lock_recorder(recorder: &std::sync::Mutex<EventRecorder>) -> Result<std::sync::MutexGuard<'_, EventRecorder>, String> {
+        recorder.lock().map_err(|_| "記録のロックに失敗しました".to_string())
+    }
+
+    pub async fn get_event_recording(&self) -> Result<RecorderStatus, String> {
+        let recorder = self.inner.read().await.recorder.clone();
+        let status = Self::lock_recorder(&recorder)?.status();
+        Ok(status)
+    }
+
+    /// 受信イベントの記録を開始・停止する（アプリを再起動すると無効に戻る）
+    pub async fn set_event_recording(&self, enabled: bool, dir: std::path::PathBuf) -> Result<RecorderStatus, String> {
+        let recorder = self.inner.read().await.recorder.clone();
+        let mut recorder = Self::lock_recorder(&recorder)?;
+        if enabled {
+            recorder.start(dir)?;
+            log::info!("受信イベントの記録を開始: {:?}", recorder.status().path);
+        } else {
+            recorder.stop();
+            log::info!("受信イベントの記録を停止");
+        }
+        Ok(recorder.status())
+    }
+
+    pub async fn get_replay_state(&self) -> ReplayState {
+        self.inner.read().await.replay_state.clone()
+    }
+
+    /// 記録したエンベロープを、受信時と同じ処理に speed 倍速で流す（実行中のリプレイは止める）
+    pub async fn start_replay(&self, path: &str, speed: f64, app_handle: &tauri::AppHandle) -> Result<ReplayState, String> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| format!("記録ファイル読み込みエラー: {}", e))?;
+        let events = crate::replay::load(&content, speed)?;
+
+        let (cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+        // リプレイはネットワークを使わない。トークンを渡さず、ユーザー・チャンネル名はキャッシュ済みの情報か ID で表示する
+        let bot_token = String::new();
+        let (generation, state) = {
+            let mut w = self.inner.write().await;
+            if let Some(cancel) = w.replay_cancel.take() {
+                let _ = cancel.send(true);
+            }
+            w.replay_generation = w.replay_generation.wrapping_add(1);
+            w.replay_cancel = Some(cancel_tx);
+            w.replay_state = ReplayState {
+                running: true,
+                processed: 0,
+                total: events.len(),
+            };
+            (w.replay_generation, w.replay_state.clone())
+        };
+        log::info!("リプレイ開始: {} ({}件, {}倍速)", path, events.len(), speed);
+        let _ = app_handle.emit("replay-state", &state);
+
+        let inner = self.inner.clone();
+        let app_handle = app_handle.clone();
+        tokio::spawn(async move {
+            let started = tokio::time::Instant::now();
+            for event in events {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(started + event.offset) => {}
+                    _ = cancel_rx.changed() => return,
+                }
+                if let Ok(socket_msg) = serde_json::from_value::<SocketModeMessage>(event.envelope) {
+                    if socket_msg.msg_type.as_deref() == Some("events_api") {
+                        if let Some(event) = socket_msg.payload.as_ref().and_then(|p| p.event.as_ref()) {
+                            let decision = Self::handle_event(inner.clone(), app_handle.clone(), bot_token.clone(), event).await;
+                            log::info!("リプレイ: {}", decision);
+                        }
+                    }
+                }
+
+                let mut w = inner.write().await;
+                if w.replay_generation != generation {
+                    return;
+                }
+                w.replay_state.processed += 1;
+                let _ = app_handle.emit("replay-state", &w.replay_state);
+            }
+
+            let mut w = inner.write().await;
+            if w.replay_generation == generation {
+                w.replay_cancel = None;
+                w.replay_state.running = false;
+                let _ = app_handle.emit("replay-state", &w.replay_state);
+                log::info!("リプレイ完了");
+            }
+        });
+        Ok(state)
+    }
+
+    pub async fn stop_replay(&self, app_handle: &tauri::AppHandle) -> ReplayState {
+        let mut w = self.inner.write().await;
+        if let Some(cancel) = w.replay_cancel.take() {
+            let _ = cancel.send(true);
+            log::info!("リプレイ停止");
+        }
+        w.replay_generation = w.replay_generation.wrapping_add(1);
+        w.replay_state.running = false;
+        let _ = app_handle.emit("replay-state", &w.replay_state);
+        w.replay_state.clone()
+    }
+
+    /// events_api のイベント1件を処理し、記録用に処理結果（表示・スキップ理由など）を返す。
+    /// 記録したイベントのリプレイも同じ処理を通す
+    async fn handle_event(
+        inner: Arc<RwLock<SlackClientInner>>,
+        app_handle: tauri::AppHandle,
+        bot_token: String,
+        event: &SlackEvent,
+    ) -> String {
+        let mut decision = String::from("ignored");
+        let event_type_str = event.event_type.as_deref().unwrap_or("unknown");
+        let subtype_str = event.subtype.as_deref().unwrap_or("");
+        let channel_str = event.channel_id().unwrap_or("(none)");
+        let (watched, watched_ids_str) = if let Some(ch) = event.channel_id() {
+            let r = inner.read().await;
+            let is_w = r.is_watched_message(ch, event.thread_ts.as_deref(), event.user.as_deref());
+            let ids: Vec<String> = r.watched_channels.iter().cloned().collect();
+            (is_w, ids.join(", "))
+        } else { (false, String::new()) };
+        let mut subtype_label = if subtype_str.is_empty() {
+            String::new()
+        } else {
+            format!(" subtype={}", subtype_str)
+        };
+        if let Some(ch_type) = event.channel_type.as_deref().filter(|t| matches!(*t, "im" | "mpim")) {
+            subtype_label.push_str(&format!(" channel_type={}", ch_type));
+        }
+        log::info!("events_api: type={}{} ch={} watched={}", event_type_str, subtype_label, channel_str, watched);
+        if !watched && !watched_ids_str.is_empty() {
+            let _ = app_handle.emit("socket-mode-debug", format!(
+                "events_api: type={}{} ch={} watched=false (監視中: [{}])",
+                event_type_str, subtype_label, channel_str, watched_ids_str
+            ));
+        } else {
+            let _ = app_handle.emit("socket-mode-debug", format!(
+                "events_api: type={}{} ch={} watched={}",
+                event_type_str, subtype_label, channel_str, watched
+            ));
+        }
+        if event.event_type.as_deref() == Some("reaction_added") || event.event_type.as_deref() == Some("reaction_removed") {
+            if let Some(item) = &event.item {
+                if let Some(item_channel) = &item.channel {
+                    let is_watched = {
+                        inner.read().await.is_reaction_target(
+                            item_channel,
+                            item.ts.as_deref().unwrap_or_default(),
+                            event.item_user.as_deref(),
+                        )
+                    };
+                    decision = if is_watched { "reaction" } else { "skipped: not watched" }.to_string();
+
+                    if is_watched {
+                        let action = if event.event_type.as_deref() == Some("reaction_added") {
+                            "added"
+                        } else {
+                            "removed"
+                        };
+                        let reaction_name = event.reaction.clone().unwrap_or_default();
+                        let user_id = event.user.clone().unwrap_or_default();
+                        let user_info = Self::fetch_user_info_static(&bot_token, &user_id, &inner).await;
+                        let (user_name, _) = inner.write().await.present_user(&user_id, &user_info);
+                        let message_ts = item.ts.clone().unwrap_or_default();
+
+                        if let Some(reactions) = Self::update_reaction_tally(
+                            &bot_token, &inner, item_channel, &message_ts, &reaction_name, &user_id, action == "added",
+                        ).await {
+                            let total: u32 = reactions.iter().map(|r| r.count).sum();
+                            let snapshot = MessageReactions {
+                                channel: item_channel.clone(),
+                                message_ts: message_ts.clone(),
+                                reactions,
+                            };
+                            let _ = app_handle.emit("slack-reactions-updated", &snapshot);
+                            Self::set_history_reactions(&app_handle, item_channel, &message_ts, snapshot.reactions.clone()).await;
+                            app_handle
+                                .state::<crate::message_queue::MessageQueueState>()
+                                .set_reactions(item_channel, &message_ts, snapshot.reactions.clone(), &app_handle)
+                                .await;
+
+                            // リアクション数が閾値に達したメッセージを一度だけ再表示
+                            let threshold = inner.read().await.config.trending_threshold.unwrap_or(0);
+                            if action == "added"
+                                && threshold > 0
+                                && total >= threshold
+                                && inner.write().await.reaction_cache.mark_trending(item_channel, &message_ts)
+                            {
+                                Self::emit_trending_message(&bot_token, &inner, &app_handle, item_channel, &message_ts).await;
+                            }
+                        }
+
+                        let reaction_event = SlackReactionEvent {
+                            action: action.to_string(),
+                            reaction: reaction_name.clone(),
+                            user: user_name,
+                            channel: item_channel.clone(),
+                            message_ts,
+                        };
+
+                        if let Err(e) = app_handle.emit("slack-reaction", &reaction_event) {
+                            log::error!("リアクションイベント送信エラー: {}", e);
+                        } else {
+                            let now = std::time::SystemTime::now();
+                            inner.write().await.last_event_at = Some(now);
+                            let secs = now.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+                            let _ = app_handle.emit("slack-last-event", secs);
+                        }
+                    }
+                }
+            }
+        } else if matches!(event.event_type.as_deref(), Some("channel_created") | Some("channel_rename") | Some("group_rename")) {
+            decision = "channel_event".to_string();
+            if event.event_type.as_deref() != Some("channel_created") {
+                Self::handle_channel_lifecycle_event(&inner, &app_handle, event).await;
+            }
+            // 新規作成・名前変更されたチャンネルをルールと照合
+            if let (Some(channel_id), Some(channel_name)) = (event.channel_id(), event.channel_name()) {
+                let channel = Self::fetch_channel_info_static(&bot_token, channel_id, &inner)
+                    .await
+                    .unwrap_or_else(|| SlackChannel {
+                        id: channel_id.to_string(),
+                        name: channel_name.to_string(),
+                        is_private: None,
+                        is_member: None,
+                        channel_type: None,
+                        is_archived: None,
+                    });
+                let storage = app_handle.state::<crate::storage::StorageState>();
+                let added = Self::apply_channel_rules_static(&bot_token, vec![channel], &inner, &storage).await;
+                if !added.is_empty() {
+                    for ch in &added {
+                        let _ = app_handle.emit("socket-mode-debug", format!(
+                            "ルールに一致したチャンネルを監視に追加: #{}", ch.name
+                        ));
+                    }
+                    let channel_name = inner.read().await.current_channel_name.clone();
+                    let _ = app_handle.emit("channel-updated", &channel_name);
+                }
+            }
+        } else if matches!(
+            event.event_type.as_deref(),
+            Some("channel_archive") | Some("group_archive")
+                | Some("channel_unarchive") | Some("group_unarchive")
+                | Some("channel_left") | Some("group_left")
+                | Some("member_left_channel") | Some("member_joined_channel")
+        ) {
+            decision = "channel_event".to_string();
+            Self::handle_channel_lifecycle_event(&inner, &app_handle, event).await;
+            if matches!(event.event_type.as_deref(), Some("member_joined_channel") | Some("member_left_channel")) {
+                Self::announce_membership_change(&bot_token, &inner, &app_handle, event).await;
+            }
+        } else if event.event_type.as_deref() == Some("message") {
+            // subtypeがある場合はスキップ（bot_message, message_changed等）
+            // channel_join/channel_leave は member_joined_channel/member_left_channel で通知する
+            if let Some(ref st) = event.subtype {
+                decision = format!("skipped: subtype={}", st);
+                let _ = app_handle.emit("socket-mode-debug", format!(
+                    "message スキップ: subtype={} ch={}", st, channel_str
+                ));
+            } else if let Some(channel) = event.channel_id() {
+                let is_watched = {
+                    inner.read().await.is_watched_message(channel, event.thread_ts.as_deref(), event.user.as_deref())
+                };
+                if !is_watched {
+                    decision = "skipped: not watched".to_string();
+                } else {
+                    // 前の区間のダイジェストを先に出してから数え直す
+                    Self::flush_digests(&bot_token, &inner, &app_handle).await;
+                    let prepared = Self::prepare_message(&bot_token, &inner, event, channel).await;
+                    match prepared.record.hidden_reason.as_deref() {
+                        Some("filter") => {
+                            let _ = app_handle.emit("socket-mode-debug", format!(
+                                "message スキップ: フィルタに一致 ch={}", channel
+                            ));
+                            let stats = inner.read().await.filter_stats.clone();
+                            let _ = app_handle.emit("filter-stats-updated", &stats);
+                        }
+                        Some("ng_word") => {
+                            let _ = app_handle.emit("socket-mode-debug", format!(
+                                "message スキップ: NGワードを含む ch={}", channel
+                            ));
+                        }
+                        Some("schedule") => {
+                            let _ = app_handle.emit("socket-mode-debug", format!(
+                                "message スキップ: メンションのみ表示する時間帯 ch={}", channel
+                            ));
+                        }
+                        Some("throttled") => {
+                            let _ = app_handle.emit("socket-mode-debug", format!(
+                                "message スキップ: 流量上限を超過 ch={}", channel
+                            ));
+                            let metrics = inner.read().await.burst_governor.metrics.clone();
+                            let _ = app_handle.emit("throttle-metrics", &metrics);
+                        }
+                        _ => {}
+                    }
+                    decision = match prepared.record.hidden_reason.as_deref() {
+                        Some(reason) => format!("skipped: {}", reason),
+                        None => "displayed".to_string(),
+                    };
+
+                    let PreparedMessage { record, message, has_content, image_jobs, parent_image_jobs, redaction } = prepared;
+                    if let Some(message) = message {
+                        let ts = message.timestamp.clone();
+                        let thread_ts = message.thread_ts.clone();
+                        Self::record_history(&app_handle, record).await;
+
+                        let preview = message.text.chars().take(50).collect::<String>();
+                        if let Err(e) = Self::emit_queue_message(&inner, &app_handle, message).await {
+                            log::error!("メッセージ送信エラー: {}", e);
+                        } else {
+                            log::info!("メッセージをフロントエンドに送信: {}", preview);
+                            if let Some(ts) = ts.as_deref() {
+                                inner.write().await.reaction_cache.track(channel, ts);
+                            }
+                            let now = std::time::SystemTime::now();
+                            inner.write().await.last_event_at = Some(now);
+                            let secs = now.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+                            let _ = app_handle.emit("slack-last-event", secs);
+                        }
+
+                        if !image_jobs.is_empty() {
+                            let channel_id = channel.to_string();
+                            let message_ts = ts.clone().unwrap_or_default();
+                            let bot_token_spawn = bot_token.clone();
+                            let app_handle_spawn = app_handle.clone();
+                            let inner_spawn = inner.clone();
+                            let redaction_spawn = redaction.clone();
+                            tokio::spawn(async move {
+                                if let Some(mut images) = Self::fetch_images(&bot_token_spawn, image_jobs).await {
+                                    redact_image_names(&mut images, redaction_spawn.as_ref());
+                                    if !images.is_empty() {
+                                        let payload = MessageImagesReady {
+                                            channel: channel_id,
+                                            timestamp: message_ts,
+                                            images,
+                                            reply_to: false,
+                                        };
+                                        Self::emit_images_ready(&inner_spawn, &app_handle_spawn, payload).await;
+                                    }
+                                }
+                            });
+                        }
+
+                        // 親メッセージの画像は取得後にキャッシュして追送
+                        if !parent_image_jobs.is_empty() {
+                        if let Some(tts) = thread_ts {
+                            let channel_id = channel.to_string();
+                            let message_ts = ts.clone().unwrap_or_default();
+                            let bot_token_spawn = bot_token.clone();
+                            let app_handle_spawn = app_handle.clone();
+                            let inner_spawn = inner.clone();
+                            tokio::spawn(async move {
+                                if let Some(mut images) = Self::fetch_images(&bot_token_spawn, parent_image_jobs).await {
+                                    inner_spawn.write().await.parent_message_cache.set_images(&channel_id, &tts, images.clone());
+                                    redact_image_names(&mut images, redaction.as_ref());
+                                    if !images.is_empty() {
+                                        let payload = MessageImagesReady {
+                                            channel: channel_id,
+                                            timestamp: message_ts,
+                                            images,
+                                            reply_to: true,
+                                        };
+                                        Self::emit_images_ready(&inner_spawn, &app_handle_spawn, payload).await;
+                                    }
+                                }
+                            });
+                        }
+                        }
+                    } else if record.hidden_reason.is_none() {
+                        decision = "skipped: empty".to_string();
+                        let _ = app_handle.emit("socket-mode-debug", format!(
+                            "message スキップ: テキスト・画像なし ch={} user={}",
+                            channel, record.user_id.as_deref().unwrap_or_default()
+                        ));
+                    } else if has_content {
+                        Self::record_history(&app_handle, record).await;
+                    }
+                }
+            }
+        }
+        decision
+    }
+
+    /// 監視対象のメッセージを判定し、画面に送る形と履歴に残す形に加工する（送信・通知はしない）。
+    /// メンション解決 → 非表示の判定 → 発言者の表示名 → 履歴の記録内容 → 表示用の加工の順に適用する
+    async fn prepare_message(
+        bot_token: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+        event: &SlackEvent,
+        channel: &str,
+    ) -> PreparedMessage {
+        let raw_text = event.text.clone().unwrap_or_default();
+        let anonymous = inner.read().await.is_anonymous();
+        let text = Self::resolve_mentions_as(&raw_text, bot_token, inner, anonymous).await;
+        let hidden_reason = inner.write().await.screen_message(
+            channel,
+            event.user.as_deref(),
+            &raw_text,
+            &text,
+            std::time::Instant::now(),
+        );
+
+        let user_id = event.user.clone().unwrap_or_default();
+        let mut attachments = Vec::new();
+        for body in event.attachments.iter().flatten().filter_map(SlackAttachment::body) {
+            attachments.push(Self::resolve_mentions_as(&body, bot_token, inner, anonymous).await);
+        }
+        let ts = event.ts.clone();
+
+        // ユーザー情報を取得
+        let user_info = Self::fetch_user_info_static(bot_token, &user_id, inner).await;
+        let (user_name, user_icon) = inner.write().await.present_user(&user_id, &user_info);
+
+        // 画像URLを収集（取得はバックグラウンドで非同期）
+        let image_jobs = collect_image_jobs(event.files.as_ref());
+
+        // フォロー中ユーザーの発言は監視外チャンネルからも届くため、発言元チャンネル名を付与
+        let channel_name = Self::fetch_channel_name_static(bot_token, channel, inner).await;
+
+        let has_content = !text.is_empty() || !attachments.is_empty() || !image_jobs.is_empty();
+
+        // 表示するかどうかに関わらず、受信した内容を実名・プレーンテキストで履歴に残す
+        let history_text = if anonymous {
+            Self::resolve_mentions_as(&raw_text, bot_token, inner, false).await
+        } else {
+            text.clone()
+        };
+        let mut record = HistoryRecord {
+            channel: Some(channel.to_string()),
+            channel_name: channel_name.clone(),
+            ts: ts.clone(),
+            thread_ts: event.thread_ts.clone(),
+            user_id: event.user.clone(),
+            user: user_display_name(&user_info),
+            text: crate::history::plain_text(&history_text),
+            hidden_reason: hidden_reason.map(str::to_string),
+            ..Default::default()
+        };
+
+        if hidden_reason.is_some() || !has_content {
+            return PreparedMessage {
+                record,
+                message: None,
+                has_content,
+                image_jobs: Vec::new(),
+                parent_image_jobs: Vec::new(),
+                redaction: None,
+            };
+        }
+
+        // スレッド返信の親メッセージ情報を取得
+        // thread_ts と ts が同じ場合はスレッドの親メッセージ自体なのでスキップ
+        let thread_ts = event.thread_ts.clone();
+        let reply_context = match thread_ts {
+            Some(ref tts) if Some(tts.as_str()) != event.ts.as_deref() => {
+                Self::fetch_reply_context(bot_token, channel, tts, inner).await
+            }
+            _ => None,
+        };
+
+        let reply_redacted = reply_context.as_ref().is_some_and(|ctx| ctx.redacted);
+        let (reply_to_user, reply_to_user_icon, reply_to_text, reply_to_images, parent_image_jobs) =
+            match reply_context {
+                Some(ctx) => (
+                    Some(ctx.user),
+                    Some(ctx.user_icon).filter(|icon| !icon.is_empty()),
+                    Some(ctx.text),
+                    ctx.images,
+                    ctx.pending_image_jobs,
+                ),
+                None => (None, None, None, None, Vec::new()),
+            };
+        let mut message = SlackMessage {
+            text,
+            user: user_name,
+            user_id: event.user.clone(),
+            user_icon,
+            channel: Some(channel.to_string()),
+            timestamp: ts,
+            queue_action: Some("addToQueue".to_string()),
+            thread_ts,
+            reply_to_user,
+            reply_to_text,
+            reply_to_user_icon,
+            reply_to_images,
+            images: None,
+            channel_name,
+            channel_type: event.channel_type.clone(),
+            trending: false,
+            highlights: Vec::new(),
+            redacted: reply_redacted,
+            attachments,
+        };
+        let redaction = {
+            let read = inner.read().await;
+            record.masked = read.apply_display_transforms(&mut message);
+            read.redaction_settings(channel).cloned()
+        };
+        record.redacted = message.redacted;
+
+        PreparedMessage {
+            record,
+            message: Some(message),
+            has_content,
+            image_jobs,
+            parent_image_jobs,
+            redaction,
+        }
+    }
+
+    /// リアクションの変更を集計に反映し、メッセージの最新のリアクション一覧を返す。
+    /// 未集計のメッセージは reactions.get で現在の状態を取得する（今回の変更も反映済み）。
+    async fn update_reaction_tally(
+        bot_token: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+        channel: &str,
+        ts: &str,
+        name: &str,
+        user: &str,
+        added: bool,
+    ) -> Option<Vec<ReactionData>> {
+        if let Some(reactions) = inner.write().await.reaction_cache.apply(channel, ts, name, user, added) {
+            return Some(reactions);
+        }
+        if bot_token.is_empty() {
+            return None;
+        }
+
+        let resp = http_client()
+            .get("https://slack.com/api/reactions.get")
+            .bearer_auth(bot_token)
+            .query(&[("channel", channel), ("timestamp", ts), ("full", "true")])
+            .send()
+            .await;
+        let result = match resp {
+            Ok(r) => r.json::<ReactionsGetResponse>().await.ok()?,
+            Err(e) => {
+                log::error!("リアクション取得エラー: {}", e);
+                return None;
+            }
+        };
+        if !result.ok {
+            log::warn!("reactions.get 失敗: {}", result.error.as_deref().unwrap_or("unknown"));
+            return None;
+        }
+
+        let reactions = result.message.map(|m| m.reactions).unwrap_or_default();
+        inner.write().await.reaction_cache.insert(channel, ts, reactions.clone());
+        Some(reactions)
+    }
+
+    /// conversations.history で対象メッセージを取得し、trending 付きでキューに再送する
+    async fn emit_trending_message(
+        bot_token: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+        app_handle: &tauri::AppHandle,
+        channel: &str,
+        ts: &str,
+    ) {
+        if bot_token.is_empty() {
+            return;
+        }
+        let resp = http_client()
+            .get("https://slack.com/api/conversations.history")
+            .bearer_auth(bot_token)
+            .query(&[("channel", channel), ("latest", ts), ("inclusive", "true"), ("limit", "1")])
+            .send()
+            .await;
+        let result = match resp {
+            Ok(r) => match r.json::<ConversationsHistoryResponse>().await {
+                Ok(result) => result,
+                Err(e) => {
+                    log::error!("メッセージ取得レスポンス解析エラー: {}", e);
+                    return;
+                }
+            },
+            Err(e) => {
+                log::error!("メッセージ取得エラー: {}", e);
+                return;
+            }
+        };
+        if !result.ok {
+            log::warn!("conversations.history APIエラー: {:?}", result.error);
+            return;
+        }
+        // スレッド返信は history に含まれず、直前の別メッセージが返るため ts を照合する
+        let Some(found) = result.messages.into_iter().find(|m| m.ts.as_deref() == Some(ts)) else {
+            log::info!("再表示対象のメッセージが見つかりません（スレッド返信の可能性）: {} {}", channel, ts);
+            return;
+        };
+
+        let user_id = found.user.unwrap_or_default();
+        let raw_text = found.text.unwrap_or_default();
+        let text = Self::resolve_mentions(&raw_text, bot_token, inner).await;
+        // 受信時と同じ条件（フィルタ・NGワード・スケジュール）で表示しないものは再表示しない
+        let hidden_reason = inner.write().await.hidden_reason(channel, Some(&user_id), &raw_text, &text);
+        if let Some(reason) = hidden_reason {
+            if reason == "filter" {
+                let stats = inner.read().await.filter_stats.clone();
+                let _ = app_handle.emit("filter-stats-updated", &stats);
+            }
+            log::info!("表示しない条件（{}）に一致するため再表示しません: {} {}", reason, channel, ts);
+            return;
+        }
+        let user_info = Self::fetch_user_info_static(bot_token, &user_id, inner).await;
+        let (user_name, user_icon) = inner.write().await.present_user(&user_id, &user_info);
+        let image_jobs = collect_image_jobs(found.files.as_ref());
+        let images = if image_jobs.is_empty() {
+            None
+        } else {
+            Self::fetch_images(bot_token, image_jobs).await
+        };
+
+        let mut message = SlackMessage {
+            user: user_name,
+            user_id: Some(user_id).filter(|id| !id.is_empty()),
+            user_icon,
+            channel: Some(channel.to_string()),
+            timestamp: Some(ts.to_string()),
+            queue_action: Some("addToQueue".to_string()),
+            thread_ts: found.thread_ts,
+            images: images.filter(|images| !images.is_empty()),
+            channel_name: Self::fetch_channel_name_static(bot_token, channel, inner).await,
+            trending: true,
+            ..SlackMessage::plain(text)
+        };
+        inner.read().await.apply_display_transforms(&mut message);
+        log::info!("リアクションが閾値に達したメッセージを再表示: {} {}", channel, ts);
+        if let Err(e) = Self::emit_queue_message(inner, app_handle, message).await {
+            log::error!("メッセージ送信エラー: {}", e);
+        }
+    }
+
+    /// メッセージをキューへ送る。一時停止中は送らずにバッファへ溜める
+    async fn emit_queue_message(
+        inner: &Arc<RwLock<SlackClientInner>>,
+        app_handle: &tauri::AppHandle,
+        message: SlackMessage,
+    ) -> tauri::Result<()> {
+        let (message, state) = {
+            let mut w = inner.write().await;
+            let message = w.pause.hold(message);
+            (message, w.pause.state())
+        };
+        match message {
+            Some(message) => Self::enqueue(app_handle, message).await,
+            None => app_handle.emit("stream-pause-state", &state),
+        }
+    }
 
-                                if socket_msg.msg_type.as_deref() == Some("disconnect") {
-                                    let reason = socket_msg.reason.as_deref().unwrap_or("unknown");
-                                    log::info!("Socket Mode disconnect: reason={}", reason);
-                                    match reason {
-                                        "link_disabled" => {
-                                            exit_reason = SocketInnerExit::LinkDisabled;
-                                            let _ = app_handle.emit(
-                                                "socket-mode-error",
-                                                "App Tokenが無効化されました（link_disabled）。トークンを再生成してください。",
-                                            );
-                                            break 'inner;
-                                        }
-                                        "warning" | "refresh_requested" => {
-                                            break 'inner;
-                                        }
-                                        _ => break 'inner,
-                                    }
-                                }
+    /// 履歴への書き込み（SQLite）は非同期ランタイムの外で行う（受信順は待ち合わせで保つ）
+    async fn record_history(app_handle: &tauri::AppHandle, record: HistoryRecord) {
+        let app_handle = app_handle.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            app_handle.state::<crate::history::HistoryState>().record(&record)
+        })
+        .await;
+        if let Err(e) = result {
+            log::warn!("履歴の記録に失敗: {}", e);
+        }
+    }
 
-                                // events_api のメッセージイベントのみ処理
-                                if socket_msg.msg_type.as_deref() == Some("events_api") {
-                                    if let Some(payload) = &socket_msg.payload {
-                                        if let Some(event) = &payload.event {
-                                            let event_type_str = event.event_type.as_deref().unwrap_or("unknown");
-                                            let subtype_str = event.subtype.as_deref().unwrap_or("");
-                                            let channel_str = event.channel.as_deref().unwrap_or("(none)");
-                                            let (watched, watched_ids_str) = if let Some(ch) = &event.channel {
-                                                let r = inner.read().await;
-                                                let is_w = r.watched_channels.contains(ch);
-                                                let ids: Vec<String> = r.watched_channels.iter().cloned().collect();
-                                                (is_w, ids.join(", "))
-                                            } else { (false, String::new()) };
-                                            let subtype_label = if subtype_str.is_empty() {
-                                                String::new()
-                                            } else {
-                                                format!(" subtype={}", subtype_str)
-                                            };
-                                            log::info!("events_api: type={}{} ch={} watched={}", event_type_str, subtype_label, channel_str, watched);
-                                            if !watched && !watched_ids_str.is_empty() {
-                                                let _ = app_handle.emit("socket-mode-debug", format!(
-                                                    "events_api: type={}{} ch={} watched=false (監視中: [{}])",
-                                                    event_type_str, subtype_label, channel_str, watched_ids_str
-                                                ));
-                                            } else {
-                                                let _ = app_handle.emit("socket-mode-debug", format!(
-                                                    "events_api: type={}{} ch={} watched={}",
-                                                    event_type_str, subtype_label, channel_str, watched
-                                                ));
-                                            }
-                                            if event.event_type.as_deref() == Some("reaction_added") || event.event_type.as_deref() == Some("reaction_removed") {
-                                                if let Some(item) = &event.item {
-                                                    if let Some(item_channel) = &item.channel {
-                                                        let is_watched = {
-                                                            inner.read().await.watched_channels.contains(item_channel)
-                                                        };
-
-                                                        if is_watched {
-                                                            let action = if event.event_type.as_deref() == Some("reaction_added") {
-                                                                "added"
-                                                            } else {
-                                                                "removed"
-                                                            };
-                                                            let reaction_name = event.reaction.clone().unwrap_or_default();
-                                                            let user_id = event.user.clone().unwrap_or_default();
-                                                            let user_info = Self::fetch_user_info_static(&bot_token, &user_id, &inner).await;
-                                                            let user_name = user_info.get("real_name")
-                                                                .or_else(|| user_info.get("name"))
-                                                                .and_then(|v| v.as_str())
-                                                                .unwrap_or("unknown")
-                                                                .to_string();
-                                                            let message_ts = item.ts.clone().unwrap_or_default();
-
-                                                            let reaction_event = SlackReactionEvent {
-                                                                action: action.to_string(),
-                                                                reaction: reaction_name.clone(),
-                                                                user: user_name,
-                                                                channel: item_channel.clone(),
-                                                                message_ts,
-                                                            };
-
-                                                            if let Err(e) = app_handle.emit("slack-reaction", &reaction_event) {
-                                                                log::error!("リアクションイベント送信エラー: {}", e);
-                                                            } else {
-                                                                let now = std::time::SystemTime::now();
-                                                                inner.write().await.last_event_at = Some(now);
-                                                                let secs = now.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
-                                                                let _ = app_handle.emit("slack-last-event", secs);
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            } else if event.event_type.as_deref() == Some("message") {
-                                                // subtypeがある場合はスキップ（bot_message, message_changed等）
-                                                if let Some(ref st) = event.subtype {
-                                                    let _ = app_handle.emit("socket-mode-debug", format!(
-                                                        "message スキップ: subtype={} ch={}", st, channel_str
-                                                    ));
-                                                } else if let Some(channel) = &event.channel {
-                                                    let is_watched = {
-                                                        inner.read().await.watched_channels.contains(channel)
-                                                    };
-
-                                                    if is_watched {
-                                                        let user_id = event.user.clone().unwrap_or_default();
-                                                        let text = event.text.clone().unwrap_or_default();
-                                                        let text = Self::resolve_mentions(&text, &bot_token, &inner).await;
-                                                        let ts = event.ts.clone();
-
-                                                        // ユーザー情報を取得
-                                                        let user_info = Self::fetch_user_info_static(&bot_token, &user_id, &inner).await;
-                                                        let user_name = user_info.get("real_name")
-                                                            .or_else(|| user_info.get("name"))
-                                                            .and_then(|v| v.as_str())
-                                                            .unwrap_or("unknown")
-                                                            .to_string();
-                                                        let user_icon = user_info.get("profile")
-                                                            .and_then(|p| p.get("image_72").or_else(|| p.get("image_48")))
-                                                            .and_then(|v| v.as_str())
-                                                            .unwrap_or("")
-                                                            .to_string();
-
-                                                        // スレッド返信の親メッセージ情報を取得
-                                                        let thread_ts = event.thread_ts.clone();
-                                                        let (reply_to_user, reply_to_text) = if let Some(ref tts) = thread_ts {
-                                                            // thread_ts と ts が同じ場合はスレッドの親メッセージ自体なのでスキップ
-                                                            if Some(tts.as_str()) != event.ts.as_deref() {
-                                                                if let Some((parent_user_id, parent_text)) = Self::fetch_parent_message_static(&bot_token, channel, tts).await {
-                                                                    // 親メッセージのユーザー名を解決
-                                                                    let parent_user_info = Self::fetch_user_info_static(&bot_token, &parent_user_id, &inner).await;
-                                                                    let parent_user_name = parent_user_info.get("real_name")
-                                                                        .or_else(|| parent_user_info.get("name"))
-                                                                        .and_then(|v| v.as_str())
-                                                                        .unwrap_or("unknown")
-                                                                        .to_string();
-                                                                    let parent_text = Self::resolve_mentions(&parent_text, &bot_token, &inner).await;
-                                                                    (Some(parent_user_name), Some(parent_text))
-                                                                } else {
-                                                                    (None, None)
-                                                                }
-                                                            } else {
-                                                                (None, None)
-                                                            }
-                                                        } else {
-                                                            (None, None)
-                                                        };
-
-                                                        // 画像URLを収集（取得はバックグラウンドで非同期）
-                                                        let mut image_jobs: Vec<(String, String, Option<String>)> = Vec::new();
-                                                        if let Some(files) = &event.files {
-                                                            for file in files {
-                                                                let mime = file.mimetype.as_deref().unwrap_or("");
-                                                                if !mime.starts_with("image/") {
-                                                                    continue;
-                                                                }
-                                                                let url = [
-                                                                    file.url_private_download.as_deref(),
-                                                                    file.thumb_480.as_deref(),
-                                                                    file.thumb_360.as_deref(),
-                                                                    file.url_private.as_deref(),
-                                                                ]
-                                                                .into_iter()
-                                                                .flatten()
-                                                                .next();
-                                                                if let Some(url) = url {
-                                                                    image_jobs.push((
-                                                                        url.to_string(),
-                                                                        mime.to_string(),
-                                                                        file.name.clone(),
-                                                                    ));
-                                                                }
-                                                            }
-                                                        }
-
-                                                        let has_text = !text.is_empty();
-                                                        let has_pending_images = !image_jobs.is_empty();
-
-                                                        if has_text || has_pending_images {
-                                                            let message = SlackMessage {
-                                                                text,
-                                                                user: user_name,
-                                                                user_icon,
-                                                                channel: Some(channel.clone()),
-                                                                timestamp: ts.clone(),
-                                                                queue_action: Some("addToQueue".to_string()),
-                                                                thread_ts,
-                                                                reply_to_user,
-                                                                reply_to_text,
-                                                                images: None,
-                                                            };
-
-                                                            if let Err(e) = app_handle.emit("add-to-text-queue", &message) {
-                                                                log::error!("メッセージ送信エラー: {}", e);
-                                                            } else {
-                                                                log::info!("メッセージをフロントエンドに送信: {}", message.text.chars().take(50).collect::<String>());
-                                                                let now = std::time::SystemTime::now();
-                                                                inner.write().await.last_event_at = Some(now);
-                                                                let secs = now.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
-                                                                let _ = app_handle.emit("slack-last-event", secs);
-                                                            }
-
-                                                            if has_pending_images {
-                                                                let channel_id = channel.clone();
-                                                                let message_ts = ts.clone().unwrap_or_default();
-                                                                let bot_token_spawn = bot_token.clone();
-                                                                let app_handle_spawn = app_handle.clone();
-                                                                tokio::spawn(async move {
-                                                                    let fetch_result = tokio::time::timeout(
-                                                                        IMAGE_FETCH_TIMEOUT,
-                                                                        async {
-                                                                            let mut image_list = Vec::new();
-                                                                            for (url, mime, name) in image_jobs {
-                                                                                if let Some(data_url) =
-                                                                                    SlackClientState::fetch_image_as_data_url(
-                                                                                        &bot_token_spawn,
-                                                                                        &url,
-                                                                                        &mime,
-                                                                                    )
-                                                                                    .await
-                                                                                {
-                                                                                    image_list.push(ImageData {
-                                                                                        data_url,
-                                                                                        name,
-                                                                                    });
-                                                                                }
-                                                                            }
-                                                                            image_list
-                                                                        },
-                                                                    )
-                                                                    .await;
-
-                                                                    if let Ok(images) = fetch_result {
-                                                                        if !images.is_empty() {
-                                                                            let payload = MessageImagesReady {
-                                                                                channel: channel_id,
-                                                                                timestamp: message_ts,
-                                                                                images,
-                                                                            };
-                                                                            let _ = app_handle_spawn
-                                                                                .emit("message-images-ready", &payload);
-                                                                        }
-                                                                    }
-                                                                });
-                                                            }
-                                                        } else {
-                                                            let _ = app_handle.emit("socket-mode-debug", format!(
-                                                                "message スキップ: テキスト・画像なし ch={} user={}",
-                                                                channel, user_id
-                                                            ));
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Some(Ok(Message::Ping(data))) => {
-                            if let Err(e) = write.send(Message::Pong(data)).await {
-                                log::error!("Pong送信エラー: {}", e);
-                            }
-                        }
-                        Some(Ok(Message::Close(_))) => {
-                            log::info!("Socket Mode WebSocket接続クローズ");
-                            break 'inner;
-                        }
-                        Some(Err(e)) => {
-                            log::error!("WebSocket受信エラー: {}", e);
-                            break 'inner;
-                        }
-                        None => {
-                            log::info!("WebSocketストリーム終了");
-                            break 'inner;
-                        }
-                        _ => {}
-                    }
+    async fn set_history_reactions(app_handle: &tauri::AppHandle, channel: &str, ts: &str, reactions: Vec<ReactionData>) {
+        let app_handle = app_handle.clone();
+        let (channel, ts) = (channel.to_string(), ts.to_string());
+        let result = tokio::task::spawn_blocking(move || {
+            app_handle
+                .state::<crate::history::HistoryState>()
+                .set_reactions(&channel, &ts, &reactions)
+        })
+        .await;
+        if let Err(e) = result {
+            log::warn!("履歴のリアクション更新に失敗: {}", e);
+        }
+    }
+
+    /// バックエンドのキューに追加し、受信したことを add-to-text-queue で通知する
+    async fn enqueue(app_handle: &tauri::AppHandle, message: SlackMessage) -> tauri::Result<()> {
+        app_handle.emit("add-to-text-queue", &message)?;
+        // 通常のメッセージは受信時に記録済み
+        if message.queue_action.as_deref() == Some("addSystemMessage") {
+            Self::record_history(app_handle, HistoryRecord::system(&message)).await;
+        }
+        app_handle
+            .state::<crate::message_queue::MessageQueueState>()
+            .push(message, app_handle)
+            .await;
+        Ok(())
+    }
+
+    /// 後から取得した画像を送る。メッセージが一時停止中のバッファにあればそちらに添付する
+    async fn emit_images_ready(
+        inner: &Arc<RwLock<SlackClientInner>>,
+        app_handle: &tauri::AppHandle,
+        payload: MessageImagesReady,
+    ) {
+        {
+            let mut w = inner.write().await;
+            let held = w.pause.find_mut(|m| {
+                m.channel.as_deref() == Some(payload.channel.as_str())
+                    && m.timestamp.as_deref() == Some(payload.timestamp.as_str())
+            });
+            if let Some(message) = held {
+                if payload.reply_to {
+                    message.reply_to_images = Some(payload.images);
+                } else {
+                    message.images = Some(payload.images);
                 }
+                return;
             }
-            }
+        }
+        let _ = app_handle.emit("message-images-ready", &payload);
+        app_handle
+            .state::<crate::message_queue::MessageQueueState>()
+            .attach_images(&payload.channel, &payload.timestamp, payload.images, payload.reply_to, app_handle)
+            .await;
+    }
 
-            inner.write().await.is_connected = false;
-            if !matches!(exit_reason, SocketInnerExit::Cancelled) {
-                let _ = app_handle.emit("socket-mode-disconnected", ());
+    /// 区間が終わったチャンネルのダイジェストをシステムメッセージとしてキューへ送る
+    async fn flush_digests(
+        bot_token: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+        app_handle: &tauri::AppHandle,
+    ) {
+        let (digests, metrics) = {
+            let mut w = inner.write().await;
+            let digests = w.burst_governor.flush_expired(std::time::Instant::now());
+            (digests, w.burst_governor.metrics.clone())
+        };
+        if digests.is_empty() {
+            return;
+        }
+
+        for digest in digests {
+            let channel_name = Self::fetch_channel_name_static(bot_token, &digest.channel, inner).await;
+            let label = channel_name.as_deref().unwrap_or(&digest.channel);
+            log::info!("流量上限によりまとめたメッセージ: #{} {}件（{}人）", label, digest.collapsed, digest.people);
+            let text = format!("#{}: ほか{}件のメッセージ（{}人）", label, digest.collapsed, digest.people);
+            let message = SlackMessage {
+                user: "Slack".to_string(),
+                channel: Some(digest.channel.clone()),
+                queue_action: Some("addSystemMessage".to_string()),
+                channel_name,
+                ..SlackMessage::plain(text)
+            };
+            if let Err(e) = Self::emit_queue_message(inner, app_handle, message).await {
+                log::error!("ダイジェスト送信エラー: {}", e);
             }
+        }
+        let _ = app_handle.emit("throttle-metrics", &metrics);
+    }
 
-            match exit_reason {
-                SocketInnerExit::Cancelled | SocketInnerExit::LinkDisabled => {
-                    break 'reconnect;
-                }
-                SocketInnerExit::Disconnected => {
-                    if *cancel_rx.borrow() {
-                        break 'reconnect;
+    /// メンバーの参加・退出を、設定で有効にした監視チャンネルに限りシステムメッセージとしてキューへ送る
+    async fn announce_membership_change(
+        bot_token: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+        app_handle: &tauri::AppHandle,
+        event: &SlackEvent,
+    ) {
+        let (Some(channel), Some(user_id)) = (event.channel_id(), event.user.as_deref()) else {
+            return;
+        };
+        let enabled = {
+            let r = inner.read().await;
+            r.watched_channels.contains(channel)
+                && r.bot_user_id.as_deref() != Some(user_id)
+                && r.config.channel_settings.get(channel).is_some_and(|s| s.announce_membership)
+        };
+        if !enabled {
+            return;
+        }
+
+        let user_info = Self::fetch_user_info_static(bot_token, user_id, inner).await;
+        let (user_name, user_icon) = inner.write().await.present_user(user_id, &user_info);
+        let channel_name = Self::fetch_channel_name_static(bot_token, channel, inner).await;
+        let label = channel_name.as_deref().unwrap_or(channel);
+        let text = if event.event_type.as_deref() == Some("member_joined_channel") {
+            format!("{} が #{} に参加しました", user_name, label)
+        } else {
+            format!("{} が #{} から退出しました", user_name, label)
+        };
+
+        let message = SlackMessage {
+            user: user_name,
+            user_id: Some(user_id.to_string()),
+            user_icon,
+            channel: Some(channel.to_string()),
+            timestamp: event.event_ts.clone(),
+            queue_action: Some("addSystemMessage".to_string()),
+            channel_name,
+            channel_type: event.channel_type.clone(),
+            ..SlackMessage::plain(text)
+        };
+        if let Err(e) = Self::emit_queue_message(inner, app_handle, message).await {
+            log::error!("システムメッセージ送信エラー: {}", e);
+        }
+    }
+
+    /// チャンネルの名前変更・アーカイブ・ボットの退出を監視設定に反映する。
+    /// 変更があれば設定を保存して channel-updated を通知し、利用者向けの警告を channel-warning で送る。
+    async fn handle_channel_lifecycle_event(
+        inner: &Arc<RwLock<SlackClientInner>>,
+        app_handle: &tauri::AppHandle,
+        event: &SlackEvent,
+    ) {
+        let Some(channel_id) = event.channel_id() else {
+            return;
+        };
+        let event_type = event.event_type.as_deref().unwrap_or("");
+
+        let (changed, warning) = {
+            let mut guard = inner.write().await;
+            let state = &mut *guard;
+            let is_bot = event.user.is_some() && event.user == state.bot_user_id;
+            let Some(data) = state.config.watched_channel_data.get_mut(channel_id) else {
+                return;
+            };
+
+            match event_type {
+                "channel_rename" | "group_rename" => match event.channel_name() {
+                    Some(new_name) if new_name != data.name => {
+                        let old_name = std::mem::replace(&mut data.name, new_name.to_string());
+                        if state.current_channel_name == old_name {
+                            state.current_channel_name = new_name.to_string();
+                        }
+                        state.channel_name_cache.remove(channel_id);
+                        (true, Some(format!("#{} のチャンネル名が #{} に変更されました", old_name, new_name)))
                     }
-                    if Self::backoff_wait(&mut cancel_rx, backoff_secs).await {
-                        break 'reconnect;
+                    _ => (false, None),
+                },
+                "channel_archive" | "group_archive" => {
+                    data.is_archived = Some(true);
+                    let name = data.name.clone();
+                    state.watched_channels.remove(channel_id);
+                    state.config.channels = state.watched_channels.iter().cloned().collect();
+                    if state.watched_channels.is_empty() {
+                        state.current_channel_name = "waigaya".to_string();
                     }
-                    backoff_secs = (backoff_secs * 2).min(MAX_BACKOFF_SECS);
-                    continue 'reconnect;
+                    (true, Some(format!("#{} がアーカイブされたため監視を停止しました", name)))
                 }
+                "channel_unarchive" | "group_unarchive" if data.is_archived == Some(true) => {
+                    data.is_archived = None;
+                    let channel = data.clone();
+                    state.insert_watched_channel(channel.clone());
+                    (true, Some(format!("#{} のアーカイブが解除されたため監視を再開しました", channel.name)))
+                }
+                "channel_left" | "group_left" => {
+                    data.is_member = Some(false);
+                    (true, Some(format!("ボットが #{} から退出しました。このチャンネルのメッセージは受信できません", data.name)))
+                }
+                "member_left_channel" if is_bot => {
+                    data.is_member = Some(false);
+                    (true, Some(format!("ボットが #{} から外されました。このチャンネルのメッセージは受信できません", data.name)))
+                }
+                "member_joined_channel" if is_bot && data.is_member == Some(false) => {
+                    data.is_member = Some(true);
+                    (true, Some(format!("ボットが #{} に再参加しました", data.name)))
+                }
+                _ => (false, None),
             }
-        }
+        };
 
-        {
-            let mut inner_guard = inner.write().await;
-            inner_guard.is_connected = false;
-            if inner_guard.socket_generation == generation {
-                inner_guard.socket_cancel = None;
-                inner_guard.socket_task = None;
-            }
+        if changed {
+            let storage = app_handle.state::<crate::storage::StorageState>();
+            Self::persist_config(inner, &storage).await;
+            let channel_name = inner.read().await.current_channel_name.clone();
+            let _ = app_handle.emit("channel-updated", &channel_name);
+        }
+        if let Some(warning) = warning {
+            log::warn!("{}", warning);
+            let _ = app_handle.emit("channel-warning", &warning);
         }
-        Ok(())
     }
 
     /// Slack画像をダウンロードしてdata URLに変換
@@ -1719,7 +4444,8 @@ impl SlackClientState {
         }
 
         if bot_token.is_empty() || user_id.is_empty() {
-            return serde_json::json!({"name": "unknown", "profile": {}});
+            let name = if user_id.is_empty() { "unknown" } else { user_id };
+            return serde_json::json!({"name": name, "profile": {}});
         }
 
         let client = reqwest::Client::builder()
@@ -1750,20 +4476,65 @@ impl SlackClientState {
         serde_json::json!({"name": "unknown", "profile": {}})
     }
 
+    /// チャンネル名を取得（static版、Socket Modeタスク内で使用）
+    /// 監視チャンネルの保存済み情報 → キャッシュ → conversations.info の順に参照する。
+    async fn fetch_channel_name_static(
+        bot_token: &str,
+        channel: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+    ) -> Option<String> {
+        {
+            let read = inner.read().await;
+            // DM の名前は相手の名前なので、匿名モードの切り替えに追従するよう保存済みの名前は使わない
+            if let Some(ch) = read.config.watched_channel_data.get(channel).filter(|ch| !ch.is_direct_message()) {
+                return Some(ch.name.clone());
+            }
+            if let Some(name) = read.channel_name_cache.get(channel) {
+                return Some(name.clone());
+            }
+        }
+
+        let channel_info = Self::fetch_channel_info_static(bot_token, channel, inner).await?;
+        inner.write().await.channel_name_cache.insert(channel.to_string(), channel_info.name.clone());
+        Some(channel_info.name)
+    }
+
+    /// 画像をまとめてdata URLとして取得（全体で IMAGE_FETCH_TIMEOUT を超えた場合は None）
+    async fn fetch_images(bot_token: &str, image_jobs: Vec<ImageJob>) -> Option<Vec<ImageData>> {
+        // トークンなし（リプレイ）では画像を取得しない
+        if bot_token.is_empty() {
+            return None;
+        }
+        tokio::time::timeout(IMAGE_FETCH_TIMEOUT, async {
+            let mut image_list = Vec::new();
+            for (url, mime, name) in image_jobs {
+                if let Some(data_url) = Self::fetch_image_as_data_url(bot_token, &url, &mime).await {
+                    image_list.push(ImageData { data_url, name });
+                }
+            }
+            image_list
+        })
+        .await
+        .ok()
+    }
+
     /// スレッドの親メッセージを取得（static版、Socket Modeタスク内で使用）
+    /// channel + thread_ts 単位でキャッシュし、同じスレッドへの返信では API を呼ばない。
     async fn fetch_parent_message_static(
         bot_token: &str,
         channel: &str,
         thread_ts: &str,
-    ) -> Option<(String, String)> {
+        inner: &Arc<RwLock<SlackClientInner>>,
+    ) -> Option<ParentMessage> {
+        if let Some(parent) = inner.read().await.parent_message_cache.get(channel, thread_ts) {
+            return Some(parent.clone());
+        }
+
         if bot_token.is_empty() {
             return None;
         }
 
-        let client = reqwest::Client::builder()
-            .timeout(HTTP_TIMEOUT)
-            .build()
-            .unwrap_or_default();
+        let client = http_client();
         let resp = client
             .get("https://slack.com/api/conversations.replies")
             .bearer_auth(bot_token)
@@ -1780,11 +4551,15 @@ impl SlackClientState {
             Ok(r) => {
                 if let Ok(result) = r.json::<ConversationsRepliesResponse>().await {
                     if result.ok {
-                        if let Some(parent) = result.messages.first() {
-                            let text = parent.text.clone().unwrap_or_default();
-                            let truncated: String = text.chars().take(50).collect();
-                            let user_id = parent.user.clone().unwrap_or_default();
-                            return Some((user_id, truncated));
+                        if let Some(first) = result.messages.into_iter().next() {
+                            let parent = ParentMessage {
+                                user_id: first.user.unwrap_or_default(),
+                                text: first.text.unwrap_or_default(),
+                                image_jobs: collect_image_jobs(first.files.as_ref()),
+                                images: None,
+                            };
+                            inner.write().await.parent_message_cache.insert(channel, thread_ts, parent.clone());
+                            return Some(parent);
                         }
                     } else {
                         log::warn!("conversations.replies APIエラー: {:?}", result.error);
@@ -1799,11 +4574,61 @@ impl SlackClientState {
         None
     }
 
+    /// スレッド返信に添える親メッセージ情報を組み立てる。
+    /// メンション解決後のテキストを設定文字数で切り詰める。
+    async fn fetch_reply_context(
+        bot_token: &str,
+        channel: &str,
+        thread_ts: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+    ) -> Option<ReplyContext> {
+        let parent = Self::fetch_parent_message_static(bot_token, channel, thread_ts, inner).await?;
+
+        let parent_user_info = Self::fetch_user_info_static(bot_token, &parent.user_id, inner).await;
+        let (parent_user, parent_user_icon) = inner.write().await.present_user(&parent.user_id, &parent_user_info);
+        let rendered = Self::resolve_mentions(&parent.text, bot_token, inner).await;
+        // 切り詰めでマスク対象が途中で切れないよう先にマスクする
+        let (rendered, redacted_count, max_chars) = {
+            let read = inner.read().await;
+            let (rendered, count) = match read.redaction_settings(channel) {
+                Some(settings) => crate::redaction::redact_rendered(&rendered, settings),
+                None => (rendered, 0),
+            };
+            (rendered, count, read.config.thread_parent_max_chars)
+        };
+
+        let pending_image_jobs = if parent.images.is_none() {
+            parent.image_jobs
+        } else {
+            Vec::new()
+        };
+
+        Some(ReplyContext {
+            user: parent_user,
+            user_icon: parent_user_icon,
+            text: truncate_rendered_text(&rendered, max_chars),
+            redacted: redacted_count > 0,
+            images: parent.images.filter(|images| !images.is_empty()),
+            pending_image_jobs,
+        })
+    }
+
     /// テキスト中の <@UXXXXX> メンションをユーザー名に置換する
     async fn resolve_mentions(
         text: &str,
         bot_token: &str,
         inner: &Arc<RwLock<SlackClientInner>>,
+    ) -> String {
+        let anonymize = inner.read().await.is_anonymous();
+        Self::resolve_mentions_as(text, bot_token, inner, anonymize).await
+    }
+
+    /// anonymize が false なら匿名モードでも実名で置換する（履歴用）
+    async fn resolve_mentions_as(
+        text: &str,
+        bot_token: &str,
+        inner: &Arc<RwLock<SlackClientInner>>,
+        anonymize: bool,
     ) -> String {
         let re = regex::Regex::new(r"<@(U[A-Z0-9]+)(?:\|[^>]*)?>").unwrap();
         let mut result = text.to_string();
@@ -1824,13 +4649,21 @@ impl SlackClientState {
 
         for user_id in user_ids {
             let user_info = Self::fetch_user_info_static(bot_token, &user_id, inner).await;
-            let display_name = user_info.get("profile")
-                .and_then(|p| p.get("display_name"))
-                .and_then(|v| v.as_str())
-                .filter(|s| !s.is_empty())
-                .or_else(|| user_info.get("real_name").and_then(|v| v.as_str()))
-                .or_else(|| user_info.get("name").and_then(|v| v.as_str()))
-                .unwrap_or("unknown");
+            let alias = if anonymize {
+                Some(inner.write().await.anonymizer.alias(&user_id))
+            } else {
+                None
+            };
+            let display_name = match alias.as_deref() {
+                Some(alias) => alias,
+                None => user_info.get("profile")
+                    .and_then(|p| p.get("display_name"))
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .or_else(|| user_info.get("real_name").and_then(|v| v.as_str()))
+                    .or_else(|| user_info.get("name").and_then(|v| v.as_str()))
+                    .unwrap_or("unknown"),
+            };
 
             // HTMLエスケープ
             let escaped_name = display_name
@@ -1855,6 +4688,411 @@ impl SlackClientState {
 
 #[cfg(test)]
 mod tests {
+    use super::{
+        apply_reaction, truncate_rendered_text, ChannelRule, ChannelRuleKind, PreparedMessage, SlackClientState, SlackConfig,
+        SlackEvent, SlackMessage,
+    };
+    use crate::filters::FilterSet;
+    use crate::highlight::HighlightSet;
+    use crate::storage::StorageState;
+    use crate::history::{HistoryEntry, HistoryQuery};
+    use crate::ng_words::{NgWordDictionary, MASK};
+    use crate::redaction::RedactionSettings;
+    use crate::schedule::QuietAction;
+    use crate::test_support::{history_enabled, history_store, ng_word, redaction_enabled};
+
+    /// 受信したメッセージイベントを、トークンなし（ネットワークを使わない）で表示・履歴用に加工する
+    async fn prepare(slack: &SlackClientState, event: serde_json::Value) -> PreparedMessage {
+        let event: SlackEvent = serde_json::from_value(event).unwrap();
+        let channel = event.channel_id().unwrap_or_default().to_string();
+        SlackClientState::prepare_message("", &slack.inner, &event, &channel).await
+    }
+
+    /// 表示名を解決できるよう、ユーザー情報をキャッシュしておく
+    async fn cache_user(slack: &SlackClientState, user_id: &str, real_name: &str) {
+        slack.inner.write().await.user_cache.insert(
+            user_id.to_string(),
+            serde_json::json!({ "name": user_id, "real_name": real_name, "profile": {} }),
+        );
+    }
+
+    #[test]
+    fn truncate_keeps_mention_span_intact() {
+        let text = r#"<span class="slack-mention">@山田太郎</span> さん確認お願いします"#;
+        assert_eq!(
+            truncate_rendered_text(text, 3),
+            r#"<span class="slack-mention">@山田</span>"#
+        );
+        assert_eq!(
+            truncate_rendered_text(text, 7),
+            r#"<span class="slack-mention">@山田太郎</span> さ"#
+        );
+    }
+
+    #[test]
+    fn truncate_counts_entities_as_one_char() {
+        assert_eq!(truncate_rendered_text("a &amp; b &lt;c&gt;", 5), "a &amp; b");
+        assert_eq!(truncate_rendered_text("short", 0), "short");
+        assert_eq!(truncate_rendered_text("short", 50), "short");
+    }
+
+    #[test]
+    fn thread_parent_max_chars_defaults_to_fifty() {
+        let config: SlackConfig = serde_json::from_str(r#"{"botToken":"","appToken":""}"#).unwrap();
+        assert_eq!(config.thread_parent_max_chars, 50);
+        assert_eq!(SlackConfig::default().thread_parent_max_chars, 50);
+    }
+
+    #[test]
+    fn reaction_tally_ignores_duplicate_add_and_drops_empty() {
+        let mut reactions = Vec::new();
+        apply_reaction(&mut reactions, "tada", "U1", true);
+        apply_reaction(&mut reactions, "tada", "U1", true);
+        apply_reaction(&mut reactions, "tada", "U2", true);
+        assert_eq!(reactions[0].count, 2);
+        assert_eq!(reactions[0].users, vec!["U1", "U2"]);
+
+        apply_reaction(&mut reactions, "tada", "U1", false);
+        apply_reaction(&mut reactions, "tada", "U2", false);
+        assert!(reactions.is_empty());
+    }
+
+    #[test]
+    fn glob_channel_rule_matches_whole_name() {
+        let rule = ChannelRule {
+            pattern: "#times-*".to_string(),
+            kind: ChannelRuleKind::Glob,
+            auto_join: false,
+        };
+        let re = rule.to_regex().unwrap();
+        assert!(re.is_match("times-yamada"));
+        assert!(re.is_match("Times-Suzuki"));
+        assert!(!re.is_match("dev-times-yamada"));
+        assert!(!re.is_match("times"));
+    }
+
+    #[test]
+    fn invalid_regex_channel_rule_is_rejected() {
+        let rule = ChannelRule {
+            pattern: "times-(".to_string(),
+            kind: ChannelRuleKind::Regex,
+            auto_join: false,
+        };
+        assert!(rule.to_regex().is_err());
+    }
+
+    #[tokio::test]
+    async fn hidden_reason_checks_filter_ng_word_then_schedule() {
+        let slack = SlackClientState::new();
+        let mut inner = slack.inner.write().await;
+        inner.filters = FilterSet::new(&serde_json::from_value::<Vec<_>>(serde_json::json!([{ "pattern": "bot" }])).unwrap());
+        inner.ng_words = NgWordDictionary::new(vec![ng_word("内緒", true)]);
+        let hidden_reason = |inner: &mut super::SlackClientInner, text: &str| inner.hidden_reason("C1", None, text, text);
+        assert_eq!(hidden_reason(&mut inner, "内緒の bot 通知"), Some("filter"));
+        assert_eq!(hidden_reason(&mut inner, "内緒の話"), Some("ng_word"));
+        assert_eq!(hidden_reason(&mut inner, "こんにちは"), None);
+        assert_eq!(inner.filter_stats.filtered_count, 1);
+
+        inner.schedule_state.action = Some(QuietAction::MentionsOnly);
+        assert_eq!(hidden_reason(&mut inner, "こんにちは"), Some("schedule"));
+        assert_eq!(inner.hidden_reason("C1", None, "<!here> こんにちは", "@here こんにちは"), None);
+    }
+
+    #[tokio::test]
+    async fn display_pipeline_filters_rendered_text_then_masks_and_highlights() {
+        let slack = SlackClientState::new();
+        let mut inner = slack.inner.write().await;
+        inner.filters = FilterSet::new(
+            &serde_json::from_value::<Vec<_>>(serde_json::json!([
+                { "pattern": "slack-mention" },
+                { "kind": "regex", "pattern": "^@佐藤" }
+            ]))
+            .unwrap(),
+        );
+        inner.config.redaction = Some(RedactionSettings {
+            custom_patterns: vec!["span".to_string()],
+            ..redaction_enabled()
+        });
+        inner.highlights = HighlightSet::new(
+            &serde_json::from_value::<Vec<_>>(serde_json::json!([
+                { "id": "incident", "pattern": "障害" },
+                { "id": "markup", "pattern": "mention" }
+            ]))
+            .unwrap(),
+        );
+        inner.ng_words = NgWordDictionary::new(vec![ng_word("秘密", false), ng_word("class", false)]);
+
+        // フィルタは画面に出す文面（マークアップを除いたメンション解決後の本文）で判定する
+        let raw = "<@U1> 秘密の障害です taro@example.com span";
+        let rendered = r#"<span class="slack-mention">@山田</span> 秘密の障害です taro@example.com span"#;
+        assert_eq!(inner.hidden_reason("C1", Some("U2"), raw, rendered), None);
+        let mentions_sato = r#"<span class="slack-mention">@佐藤</span> 確認お願いします"#;
+        assert_eq!(inner.hidden_reason("C1", Some("U2"), "<@U3> 確認お願いします", mentions_sato), Some("filter"));
+
+        let mut message = SlackMessage {
+            channel: Some("C1".to_string()),
+            ..SlackMessage::plain(rendered.to_string())
+        };
+        assert!(inner.apply_display_transforms(&mut message));
+        assert!(message.redacted);
+        assert_eq!(
+            message.text,
+            format!(r#"<span class="slack-mention">@山田</span> {}の障害です [メール] [非表示]"#, MASK)
+        );
+
+        // ハイライトは加工後の本文の UTF-16 位置で、タグの内側には付けない
+        assert_eq!(message.highlights.len(), 1);
+        let utf16: Vec<u16> = message.text.encode_utf16().collect();
+        let highlight = &message.highlights[0];
+        assert_eq!(highlight.rule_id, "incident");
+        assert_eq!(String::from_utf16(&utf16[highlight.start..highlight.end]).unwrap(), "障害");
+    }
+
+    #[tokio::test]
+    async fn fetched_thread_messages_use_the_same_filters() {
+        let slack = SlackClientState::new();
+        let mut inner = slack.inner.write().await;
+        inner.filters = FilterSet::new(
+            &serde_json::from_value::<Vec<_>>(serde_json::json!([{ "kind": "user", "pattern": "U_BOT" }])).unwrap(),
+        );
+        inner.ng_words = NgWordDictionary::new(vec![ng_word("内緒", true)]);
+
+        assert!(inner.hides_fetched("C1", Some("U_BOT"), "デプロイ完了"));
+        assert!(inner.hides_fetched("C1", Some("U1"), "内緒の話"));
+        assert!(!inner.hides_fetched("C1", Some("U1"), r#"<span class="slack-mention">@山田</span> 了解です"#));
+        assert_eq!(inner.filter_stats.filtered_count, 0);
+    }
+
+    #[tokio::test]
+    async fn attachment_text_is_redacted() {
+        let slack = SlackClientState::new();
+        let mut inner = slack.inner.write().await;
+        inner.config.redaction = Some(redaction_enabled());
+
+        let mut message = SlackMessage {
+            channel: Some("C1".to_string()),
+            attachments: vec!["問い合わせ <mailto:taro@example.com|taro@example.com>".to_string()],
+            ..SlackMessage::plain("共有します".to_string())
+        };
+        inner.apply_display_transforms(&mut message);
+        assert!(message.redacted);
+        assert_eq!(message.attachments, vec!["問い合わせ <mailto:[メール]|[メール]>"]);
+    }
+
+    #[tokio::test]
+    async fn received_message_is_redacted_after_mentions_and_before_highlights() {
+        let slack = SlackClientState::new();
+        cache_user(&slack, "U1", "山田").await;
+        {
+            let mut inner = slack.inner.write().await;
+            inner.config.redaction = Some(redaction_enabled());
+            inner.highlights = HighlightSet::new(
+                &serde_json::from_value::<Vec<_>>(serde_json::json!([{ "id": "mail", "pattern": "メール" }])).unwrap(),
+            );
+        }
+
+        let prepared = prepare(&slack, serde_json::json!({
+            "type": "message", "channel": "C1", "user": "U2", "ts": "1.0",
+            "text": "<@U1> 連絡先は taro@example.com です"
+        }))
+        .await;
+        let message = prepared.message.unwrap();
+        assert_eq!(message.text, r#"<span class="slack-mention">@山田</span> 連絡先は [メール] です"#);
+        assert!(message.redacted);
+        // ハイライトはマスク後の本文に付ける
+        assert_eq!(message.highlights.len(), 1);
+        // 履歴には元の本文を残し、マスクしたことだけを記録する
+        assert_eq!(prepared.record.text, "@山田 連絡先は taro@example.com です");
+        assert!(prepared.record.redacted);
+    }
+
+    #[tokio::test]
+    async fn received_message_is_hidden_or_masked_by_ng_words_on_resolved_text() {
+        let slack = SlackClientState::new();
+        cache_user(&slack, "U1", "山田").await;
+        slack.inner.write().await.ng_words = NgWordDictionary::new(vec![ng_word("内緒", true), ng_word("秘密", false)]);
+
+        // 非表示の語を含むメッセージは画面に送らず、理由を付けて履歴にだけ残す
+        let hidden = prepare(&slack, serde_json::json!({
+            "type": "message", "channel": "C1", "user": "U2", "ts": "1.0", "text": "<@U1> 内緒の話"
+        }))
+        .await;
+        assert!(hidden.message.is_none());
+        assert_eq!(hidden.record.hidden_reason.as_deref(), Some("ng_word"));
+        assert_eq!(hidden.record.text, "@山田 内緒の話");
+
+        let masked = prepare(&slack, serde_json::json!({
+            "type": "message", "channel": "C1", "user": "U2", "ts": "2.0", "text": "<@U1> 秘密です"
+        }))
+        .await;
+        assert_eq!(
+            masked.message.unwrap().text,
+            format!(r#"<span class="slack-mention">@山田</span> {}です"#, MASK)
+        );
+        assert!(masked.record.masked);
+        assert_eq!(masked.record.text, "@山田 秘密です");
+    }
+
+    #[tokio::test]
+    async fn anonymous_mode_aliases_display_but_history_keeps_real_names() {
+        let slack = SlackClientState::new();
+        cache_user(&slack, "U1", "山田").await;
+        cache_user(&slack, "U2", "佐藤").await;
+        slack.inner.write().await.config.anonymous_mode = Some(true);
+
+        let prepared = prepare(&slack, serde_json::json!({
+            "type": "message", "channel": "C1", "user": "U2", "ts": "1.0", "text": "<@U1> 確認お願いします"
+        }))
+        .await;
+        let message = prepared.message.unwrap();
+        // メンションを先に解決するので、本文中の相手から順に仮名を割り当てる
+        assert_eq!(message.text, r#"<span class="slack-mention">@メンバーA</span> 確認お願いします"#);
+        assert_eq!(message.user, "メンバーB");
+        assert_eq!(message.user_icon, crate::anonymize::identicon("U2"));
+        assert_eq!(prepared.record.user, "佐藤");
+        assert_eq!(prepared.record.text, "@山田 確認お願いします");
+    }
+
+    #[tokio::test]
+    async fn burst_limit_counts_only_messages_that_would_be_displayed() {
+        let slack = SlackClientState::new();
+        {
+            let mut inner = slack.inner.write().await;
+            inner.config.max_messages_per_minute = Some(2);
+            inner.ng_words = NgWordDictionary::new(vec![ng_word("内緒", true)]);
+        }
+
+        let mut reasons = Vec::new();
+        for (ts, text) in [("1.0", "おはよう"), ("2.0", "内緒の話"), ("3.0", "こんにちは"), ("4.0", "こんばんは")] {
+            let prepared = prepare(&slack, serde_json::json!({
+                "type": "message", "channel": "C1", "user": "U1", "ts": ts, "text": text
+            }))
+            .await;
+            assert_eq!(prepared.message.is_some(), prepared.record.hidden_reason.is_none());
+            reasons.push(prepared.record.hidden_reason);
+        }
+        // NGワードで隠したメッセージは上限の件数に数えない
+        assert_eq!(reasons, vec![None, Some("ng_word".to_string()), None, Some("throttled".to_string())]);
+        assert_eq!(slack.inner.read().await.burst_governor.metrics.collapsed_count, 1);
+    }
+
+    #[tokio::test]
+    async fn history_keeps_received_text_and_records_hidden_messages() {
+        let slack = SlackClientState::new();
+        cache_user(&slack, "U1", "山田").await;
+        {
+            let mut inner = slack.inner.write().await;
+            inner.filters =
+                FilterSet::new(&serde_json::from_value::<Vec<_>>(serde_json::json!([{ "pattern": "bot" }])).unwrap());
+            inner.config.redaction = Some(redaction_enabled());
+            inner.ng_words = NgWordDictionary::new(vec![ng_word("秘密", false)]);
+        }
+
+        let mut history = history_store(history_enabled());
+        for (ts, text) in [
+            ("1760000001.000100", "<@U1> 秘密の連絡先は taro@example.com"),
+            ("1760000002.000100", "bot 通知"),
+        ] {
+            let prepared = prepare(&slack, serde_json::json!({
+                "type": "message", "channel": "C1", "user": "U2", "ts": ts, "text": text
+            }))
+            .await;
+            history.record(&prepared.record, 1_760_000_000).unwrap();
+        }
+
+        // 表示したメッセージは加工前の本文と、マスク・伏せ字にしたことを残す
+        let displayed = history.search(&HistoryQuery::default()).unwrap();
+        assert_eq!(displayed.len(), 1);
+        assert_eq!(displayed[0].text, "@山田 秘密の連絡先は taro@example.com");
+        assert!(displayed[0].redacted);
+        assert!(displayed[0].masked);
+
+        let hidden = history
+            .search(&HistoryQuery {
+                query: Some("通知".to_string()),
+                include_hidden: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(hidden[0].hidden_reason.as_deref(), Some("filter"));
+    }
+
+    #[tokio::test]
+    async fn exported_history_is_masked_like_the_display() {
+        let slack = SlackClientState::new();
+        {
+            let mut inner = slack.inner.write().await;
+            inner.config.redaction = Some(redaction_enabled());
+            inner.ng_words = NgWordDictionary::new(vec![ng_word("秘密", false)]);
+        }
+        let mut entries: Vec<HistoryEntry> = serde_json::from_value(serde_json::json!([{
+            "id": 1, "channel": "C1", "user": "山田", "text": "秘密の連絡先は taro@example.com",
+            "reactions": [], "isSystem": false, "redacted": false, "masked": false, "postedAt": 0
+        }]))
+        .unwrap();
+
+        slack.mask_history_entries(&mut entries).await;
+        assert_eq!(entries[0].text, format!("{}の連絡先は [メール]", MASK));
+        assert!(entries[0].redacted);
+        assert!(entries[0].masked);
+    }
+
+    #[tokio::test]
+    async fn reactions_count_for_followed_users_and_displayed_messages() {
+        let slack = SlackClientState::new();
+        let mut inner = slack.inner.write().await;
+        inner.config.followed_users = vec!["U1".to_string()];
+        // ウォッチ中のスレッドや DM で表示したメッセージ
+        inner.reaction_cache.track("D1", "1.0");
+
+        assert!(inner.is_reaction_target("C1", "2.0", Some("U1")));
+        assert!(inner.is_reaction_target("D1", "1.0", None));
+        assert!(!inner.is_reaction_target("D1", "3.0", Some("U2")));
+    }
+
+    #[tokio::test]
+    async fn cleared_list_stays_cleared_after_saving_settings() {
+        let dir = std::env::temp_dir().join(format!("waigaya-config-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let storage = StorageState::new(dir.clone());
+        let slack = SlackClientState::new();
+        let stored: SlackConfig = serde_json::from_value(serde_json::json!({
+            "botToken": "xoxb-1",
+            "followedUsers": ["U1"],
+            "trendingThreshold": 5
+        }))
+        .unwrap();
+        slack.restore_config(stored.clone()).await;
+
+        assert!(slack.unfollow_user("U1", &storage).await.success);
+        // 設定画面は起動時に読み込んだ（U1 を含む）設定のまま保存する
+        slack
+            .update_config(SlackConfig {
+                bot_token: "xoxb-2".to_string(),
+                trending_threshold: None,
+                ..stored
+            })
+            .await;
+
+        let config = slack.get_config().await;
+        assert_eq!(config.bot_token, "xoxb-2");
+        assert!(config.followed_users.is_empty());
+        assert_eq!(config.trending_threshold, None);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn lookups_without_token_stay_offline() {
+        let slack = SlackClientState::new();
+        cache_user(&slack, "U1", "alice").await;
+
+        let cached = SlackClientState::fetch_user_info_static("", "U1", &slack.inner).await;
+        assert_eq!(cached["real_name"], "alice");
+        let unknown = SlackClientState::fetch_user_info_static("", "U2", &slack.inner).await;
+        assert_eq!(unknown["name"], "U2");
+        assert!(SlackClientState::fetch_images("", vec![("https://example.com/a.png".to_string(), "image/png".to_string(), None)]).await.is_none());
+    }
+
     #[tokio::test]
     async fn abort_terminates_spawned_task() {
         let handle = tokio::spawn(async {
@@ -4,6 +4,8 @@ use std::fs;
 use std::path::PathBuf;
 use std::sync::Mutex;
 
+use crate::message_queue::MessageQueue;
+use crate::ng_words::NgWordEntry;
 use crate::slack_client::SlackConfig;
 
 /// ストレージ管理の状態
@@ -25,6 +27,32 @@ struct StoredConfig {
     channels: Vec<String>,
     #[serde(default)]
     watched_channel_data: HashMap<String, crate::slack_client::SlackChannel>,
+    #[serde(default = "crate::slack_client::default_thread_parent_max_chars")]
+    thread_parent_max_chars: usize,
+    #[serde(default)]
+    watched_threads: Vec<crate::slack_client::WatchedThread>,
+    #[serde(default)]
+    followed_users: Vec<String>,
+    #[serde(default)]
+    channel_rules: Vec<crate::slack_client::ChannelRule>,
+    #[serde(default)]
+    channel_settings: HashMap<String, crate::slack_client::ChannelSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trending_threshold: Option<u32>,
+    #[serde(default)]
+    filter_rules: Vec<crate::filters::FilterRule>,
+    #[serde(default)]
+    highlight_rules: Vec<crate::highlight::HighlightRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    redaction: Option<crate::redaction::RedactionSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    anonymous_mode: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_messages_per_minute: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schedule: Option<crate::schedule::ScheduleSettings>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    history: Option<crate::history::HistorySettings>,
 }
 
 impl StorageState {
@@ -50,6 +78,26 @@ impl StorageState {
         self.app_data_dir.join("emojis.json")
     }
 
+    /// 受信イベントの記録先フォルダのパスを取得
+    pub fn event_log_dir(&self) -> PathBuf {
+        self.app_data_dir.join("socket-events")
+    }
+
+    /// メッセージ履歴データベースのパスを取得
+    pub fn history_path(&self) -> PathBuf {
+        self.app_data_dir.join("history.db")
+    }
+
+    /// メッセージキューのファイルのパスを取得
+    pub fn queue_path(&self) -> PathBuf {
+        self.app_data_dir.join("message-queue.json")
+    }
+
+    /// NGワード辞書ファイルのパスを取得
+    pub fn ng_words_path(&self) -> PathBuf {
+        self.app_data_dir.join("ng-words.json")
+    }
+
     /// 設定を保存
     pub fn save_config(&self, config: &SlackConfig) -> Result<(), String> {
         let stored = StoredConfig {
@@ -57,6 +105,19 @@ impl StorageState {
             app_token: config.app_token.clone(),
             channels: config.channels.clone(),
             watched_channel_data: config.watched_channel_data.clone(),
+            thread_parent_max_chars: config.thread_parent_max_chars,
+            watched_threads: config.watched_threads.clone(),
+            followed_users: config.followed_users.clone(),
+            channel_rules: config.channel_rules.clone(),
+            channel_settings: config.channel_settings.clone(),
+            trending_threshold: config.trending_threshold,
+            filter_rules: config.filter_rules.clone(),
+            highlight_rules: config.highlight_rules.clone(),
+            redaction: config.redaction.clone(),
+            anonymous_mode: config.anonymous_mode,
+            max_messages_per_minute: config.max_messages_per_minute,
+            schedule: config.schedule.clone(),
+            history: config.history.clone(),
         };
 
         let json = serde_json::to_string_pretty(&stored)
@@ -95,6 +156,19 @@ impl StorageState {
             app_token: stored.app_token,
             channels: stored.channels,
             watched_channel_data: stored.watched_channel_data,
+            thread_parent_max_chars: stored.thread_parent_max_chars,
+            watched_threads: stored.watched_threads,
+            followed_users: stored.followed_users,
+            channel_rules: stored.channel_rules,
+            channel_settings: stored.channel_settings,
+            trending_threshold: stored.trending_threshold,
+            filter_rules: stored.filter_rules,
+            highlight_rules: stored.highlight_rules,
+            redaction: stored.redaction,
+            anonymous_mode: stored.anonymous_mode,
+            max_messages_per_minute: stored.max_messages_per_minute,
+            schedule: stored.schedule,
+            history: stored.history,
         };
 
         // キャッシュに保存
@@ -160,4 +234,46 @@ impl StorageState {
         serde_json::from_str(&content)
             .map_err(|e| format!("絵文字データJSON解析エラー: {}", e))
     }
+
+    /// NGワード辞書を保存
+    pub fn save_ng_words(&self, entries: &[NgWordEntry]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(entries)
+            .map_err(|e| format!("JSON変換エラー: {}", e))?;
+        fs::write(self.ng_words_path(), json)
+            .map_err(|e| format!("NGワード保存エラー: {}", e))?;
+        log::info!("NGワード辞書を保存しました（{}件）", entries.len());
+        Ok(())
+    }
+
+    /// NGワード辞書を読み込み
+    pub fn load_ng_words(&self) -> Result<Vec<NgWordEntry>, String> {
+        let path = self.ng_words_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("NGワード読み込みエラー: {}", e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("NGワードJSON解析エラー: {}", e))
+    }
+
+    /// メッセージキューを保存
+    pub fn save_queue(&self, queue: &MessageQueue) -> Result<(), String> {
+        let json = serde_json::to_string(queue)
+            .map_err(|e| format!("JSON変換エラー: {}", e))?;
+        fs::write(self.queue_path(), json)
+            .map_err(|e| format!("キュー保存エラー: {}", e))
+    }
+
+    /// メッセージキューを読み込み
+    pub fn load_queue(&self) -> Result<MessageQueue, String> {
+        let path = self.queue_path();
+        if !path.exists() {
+            return Ok(MessageQueue::default());
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("キュー読み込みエラー: {}", e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("キューJSON解析エラー: {}", e))
+    }
 }
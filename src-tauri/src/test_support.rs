@@ -0,0 +1,48 @@
+//! 複数のモジュールのテストで使う設定・データの組み立て
+
+use std::path::Path;
+
+use crate::history::{HistoryRecord, HistorySettings, HistoryStore};
+use crate::ng_words::NgWordEntry;
+use crate::redaction::RedactionSettings;
+
+/// 組み込みのパターンをすべて有効にしたマスク設定
+pub fn redaction_enabled() -> RedactionSettings {
+    RedactionSettings {
+        enabled: true,
+        ..Default::default()
+    }
+}
+
+/// NGワード辞書の1件（hide_message なら一致したメッセージごと非表示）
+pub fn ng_word(word: &str, hide_message: bool) -> NgWordEntry {
+    NgWordEntry {
+        word: word.to_string(),
+        hide_message,
+    }
+}
+
+/// 記録を有効にした履歴の設定
+pub fn history_enabled() -> HistorySettings {
+    HistorySettings {
+        enabled: true,
+        ..Default::default()
+    }
+}
+
+/// メモリ上に作る履歴データベース
+pub fn history_store(settings: HistorySettings) -> HistoryStore {
+    HistoryStore::open(Path::new(":memory:"), settings).unwrap()
+}
+
+/// 履歴に記録する受信メッセージ（表示名はユーザーIDのまま）
+pub fn history_record(channel: &str, ts: &str, user_id: &str, text: &str) -> HistoryRecord {
+    HistoryRecord {
+        channel: Some(channel.to_string()),
+        ts: Some(ts.to_string()),
+        user_id: Some(user_id.to_string()),
+        user: user_id.to_string(),
+        text: text.to_string(),
+        ..Default::default()
+    }
+}
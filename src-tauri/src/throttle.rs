@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// 流量を数える区間
+pub const WINDOW: Duration = Duration::from_secs(60);
+
+/// まとめた件数の集計
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThrottleMetrics {
+    /// 表示せずダイジェストにまとめたメッセージ数
+    pub collapsed_count: u64,
+    /// 送信したダイジェストの数
+    pub digest_count: u64,
+    /// チャンネルID → まとめたメッセージ数
+    pub by_channel: HashMap<String, u64>,
+}
+
+/// 区間内に上限を超えた分の要約
+#[derive(Debug, Clone, PartialEq)]
+pub struct Digest {
+    pub channel: String,
+    pub collapsed: u32,
+    pub people: usize,
+}
+
+struct ChannelWindow {
+    started_at: Instant,
+    shown: u32,
+    collapsed: u32,
+    users: HashSet<String>,
+}
+
+/// チャンネルごとに1分あたりの表示件数を制限する
+#[derive(Default)]
+pub struct BurstGovernor {
+    windows: HashMap<String, ChannelWindow>,
+    pub metrics: ThrottleMetrics,
+}
+
+impl BurstGovernor {
+    /// 区間が終わったチャンネルを片付け、まとめた分があればダイジェストとして返す
+    pub fn flush_expired(&mut self, now: Instant) -> Vec<Digest> {
+        let mut digests = Vec::new();
+        self.windows.retain(|channel, window| {
+            if now.duration_since(window.started_at) < WINDOW {
+                return true;
+            }
+            if window.collapsed > 0 {
+                digests.push(Digest {
+                    channel: channel.clone(),
+                    collapsed: window.collapsed,
+                    people: window.users.len(),
+                });
+            }
+            false
+        });
+        self.metrics.digest_count += digests.len() as u64;
+        digests
+    }
+
+    /// 表示してよければ true。上限を超えた分は数えておき、区間の終わりにダイジェストにする
+    pub fn admit(&mut self, channel: &str, user_id: Option<&str>, limit: u32, now: Instant) -> bool {
+        let window = self.windows.entry(channel.to_string()).or_insert_with(|| ChannelWindow {
+            started_at: now,
+            shown: 0,
+            collapsed: 0,
+            users: HashSet::new(),
+        });
+        if window.shown < limit {
+            window.shown += 1;
+            return true;
+        }
+
+        window.collapsed += 1;
+        if let Some(user_id) = user_id {
+            window.users.insert(user_id.to_string());
+        }
+        self.metrics.collapsed_count += 1;
+        *self.metrics.by_channel.entry(channel.to_string()).or_default() += 1;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_is_collapsed_into_digest() {
+        let mut governor = BurstGovernor::default();
+        let start = Instant::now();
+
+        assert!(governor.admit("C1", Some("U1"), 2, start));
+        assert!(governor.admit("C1", Some("U2"), 2, start));
+        assert!(!governor.admit("C1", Some("U2"), 2, start));
+        assert!(!governor.admit("C1", Some("U3"), 2, start));
+        assert!(!governor.admit("C1", Some("U3"), 2, start));
+        // 他のチャンネルは別に数える
+        assert!(governor.admit("C2", Some("U1"), 2, start));
+
+        assert!(governor.flush_expired(start + Duration::from_secs(30)).is_empty());
+        let digests = governor.flush_expired(start + WINDOW);
+        assert_eq!(
+            digests,
+            vec![Digest {
+                channel: "C1".to_string(),
+                collapsed: 3,
+                people: 2,
+            }]
+        );
+        assert_eq!(governor.metrics.collapsed_count, 3);
+        assert_eq!(governor.metrics.digest_count, 1);
+
+        // 新しい区間では再び表示される
+        assert!(governor.admit("C1", Some("U1"), 2, start + WINDOW));
+    }
+}
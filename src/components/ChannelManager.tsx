@@ -1,11 +1,17 @@
 import React, { useState, useEffect } from "react"
-import { SlackChannel } from "../lib/types"
+import { SlackChannel, ChannelSettings } from "../lib/types"
 import { tauriAPI } from "../lib/tauri-api"
 
 interface ChannelManagerProps {
   isConnected: boolean
 }
 
+// DM・グループDMは「#」ではなく💬で表示
+const channelLabel = (channel: SlackChannel | undefined, fallback: string): string => {
+  const name = channel?.name || fallback
+  return channel?.channel_type ? `💬 ${name}` : `#${name}`
+}
+
 export const ChannelManager: React.FC<ChannelManagerProps> = ({
   isConnected,
 }) => {
@@ -18,6 +24,10 @@ export const ChannelManager: React.FC<ChannelManagerProps> = ({
   const [channelSearch, setChannelSearch] = useState("")
   const [selectedChannel, setSelectedChannel] = useState("")
   const [isLoadingChannels, setIsLoadingChannels] = useState(false)
+  const [autoJoin, setAutoJoin] = useState(false)
+  const [channelSettings, setChannelSettings] = useState<{
+    [key: string]: ChannelSettings
+  }>({})
 
   // 監視中のチャンネル一覧を取得
   const loadWatchedChannels = async () => {
@@ -25,6 +35,12 @@ export const ChannelManager: React.FC<ChannelManagerProps> = ({
       const result = await tauriAPI.getWatchedChannels()
       setWatchedChannels(result.ids)
       setWatchedChannelData(result.data)
+      const settings = await Promise.all(
+        result.ids.map((id) => tauriAPI.getChannelSettings(id))
+      )
+      setChannelSettings(
+        Object.fromEntries(result.ids.map((id, i) => [id, settings[i]]))
+      )
     } catch (error) {
       console.error("監視チャンネル取得エラー:", error)
     }
@@ -56,13 +72,29 @@ export const ChannelManager: React.FC<ChannelManagerProps> = ({
     }
   }
 
+  // メンバーの参加・退出表示を切り替え
+  const toggleAnnounceMembership = async (channelId: string) => {
+    const current = channelSettings[channelId] ?? {}
+    const updated = { ...current, announceMembership: !current.announceMembership }
+    try {
+      const result = await tauriAPI.setChannelSettings(channelId, updated)
+      if (result.success) {
+        setChannelSettings((prev) => ({ ...prev, [channelId]: updated }))
+      } else {
+        alert(`チャンネル設定エラー: ${result.error}`)
+      }
+    } catch (error) {
+      console.error("チャンネル設定エラー:", error)
+    }
+  }
+
   // チャンネルを監視に追加
   const addChannel = async () => {
     if (!selectedChannel) return
 
     console.log("➕ チャンネル追加リクエスト:", selectedChannel)
     try {
-      const result = await tauriAPI.addWatchChannel(selectedChannel)
+      const result = await tauriAPI.addWatchChannel(selectedChannel, autoJoin)
       console.log("➕ チャンネル追加結果:", result)
       if (result.success) {
         await loadWatchedChannels() // 監視リストを更新
@@ -150,7 +182,7 @@ export const ChannelManager: React.FC<ChannelManagerProps> = ({
                 key={channelId}
                 className="inline-block bg-green-100 text-green-800 px-2 py-1 rounded-sm text-xs mr-2 mb-1"
               >
-                #{channelInfo?.name || channelId}
+                {channelLabel(channelInfo, channelId)}
                 <button
                   onClick={() => removeChannel(channelId)}
                   className="ml-1 text-red-600 hover:text-red-800"
@@ -196,7 +228,15 @@ export const ChannelManager: React.FC<ChannelManagerProps> = ({
                           key={channelId}
                           className="inline-block bg-green-100 text-green-800 px-2 py-1 rounded-sm text-sm mr-2 mb-1"
                         >
-                          #{channelInfo?.name || channelId}
+                          {channelLabel(channelInfo, channelId)}
+                          <button
+                            onClick={() => toggleAnnounceMembership(channelId)}
+                            className="ml-1"
+                            style={{ opacity: channelSettings[channelId]?.announceMembership ? 1 : 0.3 }}
+                            title="メンバーの参加・退出を表示"
+                          >
+                            📣
+                          </button>
                           <button
                             onClick={() => removeChannel(channelId)}
                             className="ml-1 text-red-600 hover:text-red-800"
@@ -261,16 +301,24 @@ export const ChannelManager: React.FC<ChannelManagerProps> = ({
                         <option
                           key={channel.id}
                           value={channel.id}
-                          disabled={!channel.is_member}
+                          disabled={!channel.is_member && (!autoJoin || !!channel.is_private)}
                         >
-                          #{channel.name}{" "}
-                          {channel.is_private ? "(プライベート)" : ""}
+                          {channelLabel(channel, channel.id)}{" "}
+                          {channel.is_private && !channel.channel_type ? "(プライベート)" : ""}
                           {!channel.is_member ? " (未参加)" : ""}
                         </option>
                       ))}
                     </>
                   )}
                 </select>
+                <label className="flex items-center gap-2 mb-2 text-sm">
+                  <input
+                    type="checkbox"
+                    checked={autoJoin}
+                    onChange={(e) => setAutoJoin(e.target.checked)}
+                  />
+                  未参加のパブリックチャンネルにボットを自動参加させる（channels:join スコープが必要）
+                </label>
                 <button
                   onClick={addChannel}
                   disabled={!selectedChannel || !isConnected}
@@ -1,6 +1,6 @@
 import React, { useState, useEffect, useRef } from "react"
 import { motion, AnimatePresence } from "framer-motion"
-import { SlackMessage, ReactionData, SlackReactionEvent, DisplayMessageImagesUpdate } from "../lib/types"
+import { SlackMessage, QueueItem, ReactionData, MessageReactions, DisplayMessageImagesUpdate, Highlight } from "../lib/types"
 import { tauriAPI } from "../lib/tauri-api"
 import { getDisplaySettings, DisplaySettings } from "./DisplaySettings"
 import { emojiConverter } from "../lib/emoji-converter"
@@ -18,16 +18,25 @@ export const DisplayWindow: React.FC = () => {
     // 初期チャンネル名を取得
     tauriAPI.getCurrentChannelName().then(setChannelName)
 
+    const toDisplayMessage = (message: SlackMessage & Partial<QueueItem>): DisplayMessage => ({
+      ...message,
+      id: `msg-${Date.now()}-${Math.random()}`,
+      reactions: message.reactions ?? [],
+    })
+
     const handleMessage = (message: SlackMessage) => {
-      const displayMessage: DisplayMessage = {
-        ...message,
-        id: `msg-${Date.now()}-${Math.random()}`,
-        reactions: [],
-      }
-      setMessages((prev) => [displayMessage, ...prev.slice(0, 19)])
+      setMessages((prev) => [toDisplayMessage(message), ...prev.slice(0, 19)])
     }
 
-    const handleReaction = (event: SlackReactionEvent) => {
+    // ウィンドウを開き直したときは、バックエンドのキューから表示済みのメッセージを復元する
+    tauriAPI.queueGetState().then((state) => {
+      if (state.currentIndex === null) return
+      const shown = state.items.slice(0, state.currentIndex + 1).slice(-20).reverse()
+      setMessages((prev) => (prev.length > 0 ? prev : shown.map(toDisplayMessage)))
+    }).catch(() => {})
+
+    // バックエンドで集計済みのリアクション一覧で置き換える
+    const handleReactions = (event: MessageReactions) => {
       setMessages((prev) => {
         const idx = prev.findIndex(
           (m) => m.channel === event.channel && m.timestamp === event.message_ts,
@@ -35,36 +44,7 @@ export const DisplayWindow: React.FC = () => {
         if (idx === -1) return prev
 
         const updated = [...prev]
-        const msg = { ...updated[idx], reactions: [...updated[idx].reactions] }
-
-        if (event.action === "added") {
-          const existing = msg.reactions.findIndex((r) => r.name === event.reaction)
-          if (existing >= 0) {
-            msg.reactions[existing] = {
-              ...msg.reactions[existing],
-              count: msg.reactions[existing].count + 1,
-              users: [...msg.reactions[existing].users, event.user],
-            }
-          } else {
-            msg.reactions.push({ name: event.reaction, count: 1, users: [event.user] })
-          }
-        } else if (event.action === "removed") {
-          const existing = msg.reactions.findIndex((r) => r.name === event.reaction)
-          if (existing >= 0) {
-            const newCount = msg.reactions[existing].count - 1
-            if (newCount <= 0) {
-              msg.reactions.splice(existing, 1)
-            } else {
-              msg.reactions[existing] = {
-                ...msg.reactions[existing],
-                count: newCount,
-                users: msg.reactions[existing].users.filter((u) => u !== event.user),
-              }
-            }
-          }
-        }
-
-        updated[idx] = msg
+        updated[idx] = { ...updated[idx], reactions: event.reactions }
         return updated
       })
     }
@@ -76,7 +56,9 @@ export const DisplayWindow: React.FC = () => {
         )
         if (idx === -1) return prev
         const updated = [...prev]
-        updated[idx] = { ...updated[idx], images: update.images }
+        updated[idx] = update.replyTo
+          ? { ...updated[idx], replyToImages: update.images }
+          : { ...updated[idx], images: update.images }
         return updated
       })
     }
@@ -88,7 +70,7 @@ export const DisplayWindow: React.FC = () => {
       tauriAPI.onDisplayMessageImagesUpdate(handleImagesUpdate)
 
     const cleanupReactionListener =
-      tauriAPI.onSlackReaction(handleReaction)
+      tauriAPI.onSlackReactionsUpdated(handleReactions)
 
     const cleanupEmojiListener = tauriAPI.onCustomEmojisData(
       (data: any) => {
@@ -136,7 +118,7 @@ export const DisplayWindow: React.FC = () => {
       <div className="flex flex-col overflow-hidden">
         <AnimatePresence>
           {messages.map((message) => (
-            <MessageItem key={message.id} message={message} />
+            <MessageItem key={message.id} message={message} currentChannelName={channelName} />
           ))}
         </AnimatePresence>
       </div>
@@ -146,9 +128,10 @@ export const DisplayWindow: React.FC = () => {
 
 interface MessageItemProps {
   message: DisplayMessage
+  currentChannelName: string
 }
 
-const MessageItem: React.FC<MessageItemProps> = ({ message }) => {
+const MessageItem: React.FC<MessageItemProps> = ({ message, currentChannelName }) => {
   const [displaySettings, setDisplaySettings] =
     useState<DisplaySettings>(getDisplaySettings())
 
@@ -172,10 +155,31 @@ const MessageItem: React.FC<MessageItemProps> = ({ message }) => {
 
   const hasText = !!message.text
   const hasImages = message.images && message.images.length > 0
-  if (!hasText && !hasImages) {
+  const hasAttachments = !!message.attachments && message.attachments.length > 0
+  if (!hasText && !hasImages && !hasAttachments) {
     return null
   }
 
+  // 参加・退出などのシステムメッセージは1行で控えめに表示
+  if (message.isSystem) {
+    return (
+      <motion.div
+        layout
+        initial={{ opacity: 0 }}
+        animate={{ opacity: 0.7 }}
+        exit={{ opacity: 0, transition: { duration: 0.2 } }}
+        transition={{ duration: 0.4, ease: "easeOut" }}
+        style={{ color: displaySettings.textColor }}
+        className="px-2 py-1 text-xs italic flex items-center gap-1"
+      >
+        {message.userIcon && (
+          <img src={message.userIcon} className="w-4 h-4 rounded-sm" alt={message.user} />
+        )}
+        {message.text}
+      </motion.div>
+    )
+  }
+
   return (
     <motion.div
       layout
@@ -196,8 +200,28 @@ const MessageItem: React.FC<MessageItemProps> = ({ message }) => {
             className="text-xs truncate"
             style={{ color: displaySettings.textColor, maxWidth: "100%" }}
           >
-            <span className="text-xs">↩</span>「<span dangerouslySetInnerHTML={{ __html: emojiConverter.convertEmojisToReact(message.replyToText!) }} />」
+            <span className="text-xs">↩</span>
+            {message.replyToUserIcon && (
+              <img
+                src={message.replyToUserIcon}
+                className="inline-block w-4 h-4 rounded-sm mx-1 align-text-bottom"
+                alt={message.replyToUser}
+              />
+            )}
+            「<span dangerouslySetInnerHTML={{ __html: emojiConverter.convertEmojisToReact(message.replyToText!) }} />」
           </div>
+          {message.replyToImages && message.replyToImages.length > 0 && (
+            <div className="flex flex-wrap gap-1 mt-0.5">
+              {message.replyToImages.map((img, idx) => (
+                <img
+                  key={idx}
+                  src={img.dataUrl}
+                  alt={img.name || "image"}
+                  className="rounded-sm max-h-12 object-contain"
+                />
+              ))}
+            </div>
+          )}
         </div>
       )}
       <div className="flex gap-2">
@@ -212,6 +236,22 @@ const MessageItem: React.FC<MessageItemProps> = ({ message }) => {
             style={{ color: displaySettings.textColor }}
           >
             {message.user}
+            {message.redacted && (
+              <span className="ml-2 text-xs font-normal" title="一部の情報をマスクしています">
+                🛡️
+              </span>
+            )}
+            {message.trending && (
+              <span className="ml-2 text-xs font-normal" title="リアクションが集まっています">
+                🔥
+              </span>
+            )}
+            {message.channelName && message.channelName !== currentChannelName && (
+              <span className="ml-2 text-xs font-normal" style={{ opacity: 0.7 }}>
+                {message.channelType === "im" || message.channelType === "mpim" ? "💬 " : "#"}
+                {message.channelName}
+              </span>
+            )}
           </div>
           {hasText && (
             <div
@@ -221,10 +261,24 @@ const MessageItem: React.FC<MessageItemProps> = ({ message }) => {
               }}
               className="font-normal leading-snug tracking-tight"
               dangerouslySetInnerHTML={{
-                __html: emojiConverter.convertEmojisToReact(message.text),
+                __html: emojiConverter.convertEmojisToReact(applyHighlights(message.text, message.highlights)),
               }}
             />
           )}
+          {hasAttachments &&
+            message.attachments!.map((attachment, idx) => (
+              <div
+                key={idx}
+                className="mt-1 pl-2 text-sm leading-snug whitespace-pre-wrap"
+                style={{
+                  borderLeft: `3px solid ${displaySettings.textColor}`,
+                  opacity: 0.8,
+                }}
+                dangerouslySetInnerHTML={{
+                  __html: emojiConverter.convertEmojisToReact(attachment),
+                }}
+              />
+            ))}
           {hasImages && (
             <div className="flex flex-wrap gap-1 mt-1">
               {message.images!.map((img, idx) => (
@@ -267,6 +321,21 @@ const MessageItem: React.FC<MessageItemProps> = ({ message }) => {
   )
 }
 
+// バックエンドで求めた範囲（UTF-16 オフセット）を <mark> で囲む（後ろから挿入して位置をずらさない）
+function applyHighlights(text: string, highlights?: Highlight[]): string {
+  if (!highlights || highlights.length === 0) return text
+  let result = text
+  for (const h of [...highlights].sort((a, b) => b.start - a.start)) {
+    const color = h.color?.replace(/[";<>]/g, "")
+    const style = color ? ` style="background-color: ${color}"` : ""
+    result =
+      result.slice(0, h.start) +
+      `<mark class="rounded-sm px-0.5"${style}>${result.slice(h.start, h.end)}</mark>` +
+      result.slice(h.end)
+  }
+  return result
+}
+
 function hexToRgba(hex: string, alpha: number): string {
   let c = hex.replace("#", "")
   if (c.length === 3) {
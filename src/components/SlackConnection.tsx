@@ -1,7 +1,7 @@
-import React, { useState, useEffect } from "react"
+import React, { useState, useEffect, useRef } from "react"
 import { listen } from "@tauri-apps/api/event"
 import { openUrl } from "@tauri-apps/plugin-opener"
-import { SlackConfig, SlackMessage, SlackReactionEvent, MessageImagesReady } from "../lib/types"
+import { SlackConfig, SlackMessage, SlackReactionEvent, MessageImagesReady, ChannelHealth, FilterStats, ThrottleMetrics, PauseState, ResumeMode, ScheduleState, ReplayState } from "../lib/types"
 import { tauriAPI } from "../lib/tauri-api"
 import { ChannelManager } from "./ChannelManager"
 import { DisplaySettingsComponent, DisplaySettings } from "./DisplaySettings"
@@ -46,6 +46,8 @@ export const SlackConnection: React.FC = () => {
   const [showEmojiManager, setShowEmojiManager] = useState(false)
   const [showChannelManager, setShowChannelManager] = useState(false)
   const [lastEventAt, setLastEventAt] = useState<Date | null>(null)
+  const [pauseState, setPauseState] = useState<PauseState>({ paused: false, scheduled: false, buffered: 0, dropped: 0 })
+  const pausedRef = useRef(false)
   const { logs, addLog, clearLogs } = useLogger()
 
   // 初期化時に保存された設定を読み込み
@@ -55,43 +57,21 @@ export const SlackConnection: React.FC = () => {
 
     loadSavedConfig()
 
-    // TextQueueのコールバックを設定
-    textQueue.setMessageCallback((message: SlackMessage) => {
-      console.log("📤 TextQueueからメッセージ送信:", message)
-      console.log("📤 TextQueue -> DisplayWindow経由でメッセージ送信:", {
-        text: message.text?.substring(0, 50),
-        user: message.user,
-        hasIcon: !!message.userIcon,
-      })
+    // 表示されたメッセージをログに残す（キューと再生はバックエンドで管理）
+    const unlistenDisplay = tauriAPI.onDisplaySlackMessage((message) => {
       addLog("info", "メッセージ", `表示: ${message.text?.substring(0, 40) ?? "(テキストなし)"}`)
-      tauriAPI.displaySlackMessage(message)
-    })
-
-    textQueue.setImagesUpdatedCallback((update) => {
-      tauriAPI.displayMessageImagesUpdate(update)
     })
 
     let unlistenImagesReady: (() => void) | null = null
 
-    // SlackメッセージをTextQueueに追加する要求を受信（直接listenでReact Strict Mode対応）
+    // バックエンドのキューに追加されたSlackメッセージの通知（直接listenでReact Strict Mode対応）
     listen<SlackMessage>('add-to-text-queue', (event) => {
       const message = event.payload
       console.log(
-        "📨 SlackメッセージをTextQueueに追加:",
+        "📨 Slackメッセージをキューに追加:",
         message.text?.substring(0, 50) || "テキストなし"
       )
-      console.log("📨 受信メッセージ詳細:", {
-        text: message.text,
-        user: message.user,
-        userIcon: message.userIcon,
-        _queueAction: message._queueAction,
-      })
-      // _queueActionフラグを削除してTextQueueに追加
-      const cleanMessage = { ...message }
-      delete cleanMessage._queueAction
-      console.log("📨 TextQueue追加前のメッセージ:", cleanMessage)
       addLog("info", "メッセージ", `受信: ${message.text?.substring(0, 40) ?? "(テキストなし)"}`)
-      textQueue.addSlackMessage(cleanMessage)
     }).then((fn) => {
       if (cancelled) { fn(); return }
       unlistenAddToQueue = fn
@@ -101,9 +81,8 @@ export const SlackConnection: React.FC = () => {
     })
 
     listen<MessageImagesReady>('message-images-ready', (event) => {
-      const { channel, timestamp, images } = event.payload
-      textQueue.attachImages(channel, timestamp, images)
-      addLog("info", "メッセージ", `画像追送: ch=${channel} ts=${timestamp}`)
+      const { channel, timestamp, replyTo } = event.payload
+      addLog("info", "メッセージ", `${replyTo ? "親メッセージ画像追送" : "画像追送"}: ch=${channel} ts=${timestamp}`)
     }).then((fn) => {
       if (cancelled) { fn(); return }
       unlistenImagesReady = fn
@@ -115,7 +94,7 @@ export const SlackConnection: React.FC = () => {
       cancelled = true
       if (unlistenAddToQueue) unlistenAddToQueue()
       if (unlistenImagesReady) unlistenImagesReady()
-      textQueue.clear()
+      unlistenDisplay()
     }
   }, [addLog])
 
@@ -129,6 +108,43 @@ export const SlackConnection: React.FC = () => {
         addLog("info", "ユーザー", `ユーザーデータ更新: ${e.payload}件`)),
       listen<string>('channel-updated', (e) =>
         addLog("info", "チャンネル", `チャンネル変更: ${e.payload}`)),
+      listen<string>('channel-warning', (e) =>
+        addLog("warn", "チャンネル", `⚠️ ${e.payload}`)),
+      listen<FilterStats>('filter-stats-updated', (e) =>
+        addLog("info", "フィルタ", `フィルタで非表示: 累計${e.payload.filtered_count}件`)),
+      listen<PauseState>('stream-pause-state', (e) => {
+        // 表示中のキューも止め、再開時に続きから流す
+        if (e.payload.paused && !pausedRef.current) {
+          textQueue.pause()
+          addLog("info", "一時停止", "⏸ メッセージの表示を一時停止しました")
+        } else if (!e.payload.paused && pausedRef.current) {
+          textQueue.resume()
+          addLog("info", "一時停止", "▶️ メッセージの表示を再開しました")
+        }
+        pausedRef.current = e.payload.paused
+        setPauseState(e.payload)
+      }),
+      listen<ScheduleState>('schedule-state', (e) =>
+        addLog("info", "スケジュール", e.payload.quiet
+          ? `🌙 ${e.payload.reason}のため${e.payload.action === 'mentionsOnly' ? "メンションのみ表示します" : "表示を止めます"}`
+          : "☀️ スケジュールによる制限を解除しました")),
+      listen<ReplayState>('replay-state', (e) => {
+        if (e.payload.running && e.payload.processed === 0) {
+          addLog("info", "リプレイ", `▶️ ${e.payload.total}件のイベントをリプレイします`)
+        } else if (!e.payload.running) {
+          addLog("info", "リプレイ", `⏹ リプレイ終了（${e.payload.processed}/${e.payload.total}件）`)
+        }
+      }),
+      listen<ThrottleMetrics>('throttle-metrics', (e) =>
+        addLog("info", "流量制限", `ダイジェストにまとめた件数: 累計${e.payload.collapsedCount}件`)),
+      listen<ChannelHealth[]>('channel-health', (e) => {
+        const unhealthy = e.payload.filter((c) => c.status !== 'ok')
+        if (unhealthy.length === 0) {
+          addLog("info", "チャンネル", `✅ 監視チャンネル${e.payload.length}件を確認しました`)
+        }
+        unhealthy.forEach((c) =>
+          addLog("warn", "チャンネル", `⚠️ #${c.name}: ${c.status}${c.error ? ` (${c.error})` : ""}`))
+      }),
       listen('display-settings-update', () =>
         addLog("info", "設定", "表示設定が変更されました")),
       listen('custom-emojis-data', () =>
@@ -396,7 +412,7 @@ export const SlackConnection: React.FC = () => {
     }
 
     console.log("🎯 サンプルメッセージをTextQueueに追加:", sampleMessage)
-    // 現行システムと同じ動作：TextQueueに追加して3秒間隔で表示
+    // Slackからのメッセージと同じくバックエンドのキューに追加して3秒間隔で表示
     textQueue.addSlackMessage(sampleMessage)
   }
 
@@ -406,6 +422,22 @@ export const SlackConnection: React.FC = () => {
     // この機能は後で実装
   }
 
+  const handleResume = async (mode: ResumeMode) => {
+    try {
+      await tauriAPI.resumeStream(mode)
+    } catch (error) {
+      addLog("error", "一時停止", `再開に失敗しました: ${error}`)
+    }
+  }
+
+  const handlePause = async () => {
+    try {
+      await tauriAPI.pauseStream()
+    } catch (error) {
+      addLog("error", "一時停止", `一時停止に失敗しました: ${error}`)
+    }
+  }
+
   const formatLastEvent = (date: Date | null): string => {
     if (!date) return "受信なし"
     const mins = Math.floor((Date.now() - date.getTime()) / 60000)
@@ -461,6 +493,30 @@ export const SlackConnection: React.FC = () => {
               {lastEventWarning && " — Event Subscriptions がオフになっていないか確認してください"}
             </div>
           )}
+          {isConnected && (
+            <div className="text-xs mt-2 flex items-center gap-1 flex-wrap">
+              {pauseState.paused ? (
+                <>
+                  <span className="text-amber-600">
+                    ⏸ 一時停止中（{pauseState.buffered}件保持{pauseState.dropped > 0 && `、${pauseState.dropped}件破棄`}）
+                  </span>
+                  <button className="bg-green-600 text-white px-2 py-0.5 rounded-sm hover:bg-green-700" onClick={() => handleResume('all')}>
+                    すべて再生
+                  </button>
+                  <button className="bg-blue-600 text-white px-2 py-0.5 rounded-sm hover:bg-blue-700" onClick={() => handleResume('summary')}>
+                    要約して再開
+                  </button>
+                  <button className="bg-gray-500 text-white px-2 py-0.5 rounded-sm hover:bg-gray-600" onClick={() => handleResume('discard')}>
+                    破棄して再開
+                  </button>
+                </>
+              ) : (
+                <button className="bg-gray-200 text-gray-700 px-2 py-0.5 rounded-sm hover:bg-gray-300" onClick={handlePause}>
+                  ⏸ 表示を一時停止
+                </button>
+              )}
+            </div>
+          )}
         </div>
 
         {/* ユーザー一覧管理機能（現行システムと同等） */}
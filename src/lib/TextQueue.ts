@@ -1,18 +1,7 @@
-import { SlackMessage, ImageData, DisplayMessageImagesUpdate } from './types';
+import { SlackMessage, QueueItem, MessageQueueState } from './types';
+import { tauriAPI } from './tauri-api';
 
-export interface QueueItem {
-  id: number;
-  text: string;
-  user?: string;
-  userIcon?: string;
-  timestamp: string;
-  type: 'slack' | 'text';
-  replyToUser?: string;
-  replyToText?: string;
-  images?: ImageData[];
-  channel?: string;
-  slackTs?: string;
-}
+export type { QueueItem };
 
 export interface DisplaySettings {
   fontSize: number;
@@ -21,17 +10,20 @@ export interface DisplaySettings {
   fontColor: string;
 }
 
+/**
+ * メッセージキューの操作窓口
+ * キュー本体と再生タイマーはバックエンド（Rust）にあり、ここでは queue-updated / queue-position
+ * イベントで受け取った状態を保持してコマンドを呼ぶだけ
+ */
 export class TextQueue {
   private queue: QueueItem[] = [];
   private currentIndex: number = -1;
-  private currentTimer: ReturnType<typeof setTimeout> | null = null;
   private isPlaying: boolean = false;
-  private maxQueueSize: number = 50; // TypeScript版で追加：キューサイズ制限
 
   // 表示設定
   private displaySettings: DisplaySettings = {
     fontSize: 20,
-    bgColor: "#000000", 
+    bgColor: "#000000",
     bgAlpha: 0.5,
     fontColor: "#ffffff"
   };
@@ -39,49 +31,41 @@ export class TextQueue {
   private readonly DEFAULT_SETTINGS: DisplaySettings = {
     fontSize: 20,
     bgColor: "#000000",
-    bgAlpha: 0.5, 
+    bgAlpha: 0.5,
     fontColor: "#ffffff"
   };
 
   // コールバック関数
-  private onMessageSend: ((message: SlackMessage) => void) | null = null;
-  private onImagesUpdated: ((update: DisplayMessageImagesUpdate) => void) | null = null;
   private onUIUpdate: ((queue: QueueItem[], currentIndex: number, isPlaying: boolean) => void) | null = null;
 
   constructor() {
     this.loadDisplaySettings();
-    this.updateUI();
+    this.subscribe();
   }
 
-  // コールバック設定
-  setMessageCallback(callback: (message: SlackMessage) => void): void {
-    this.onMessageSend = callback;
-  }
-
-  setImagesUpdatedCallback(callback: (update: DisplayMessageImagesUpdate) => void): void {
-    this.onImagesUpdated = callback;
+  // バックエンドのキューの状態を購読
+  private subscribe(): void {
+    tauriAPI.onQueueUpdated((state) => this.applyState(state));
+    tauriAPI.onQueuePosition((position) => {
+      this.currentIndex = position.currentIndex ?? -1;
+      this.isPlaying = position.isPlaying;
+      this.updateUI();
+    });
+    tauriAPI.queueGetState()
+      .then((state) => this.applyState(state))
+      .catch((error) => console.error('❌ キュー状態取得エラー:', error));
   }
 
-  attachImages(channel: string, slackTs: string, images: ImageData[]): void {
-    const idx = this.queue.findIndex(
-      (item) => item.channel === channel && item.slackTs === slackTs,
-    );
-    if (idx === -1) {
-      console.log('📷 画像追送スキップ: キューに該当メッセージなし', { channel, slackTs });
-      return;
-    }
-
-    this.queue[idx] = { ...this.queue[idx], images };
+  private applyState(state: MessageQueueState): void {
+    this.queue = state.items;
+    this.currentIndex = state.currentIndex ?? -1;
+    this.isPlaying = state.isPlaying;
     this.updateUI();
-
-    if (this.onImagesUpdated) {
-      this.onImagesUpdated({ channel, timestamp: slackTs, images });
-      console.log('📷 画像をDisplayへ追送:', { channel, slackTs });
-    }
   }
 
   setUIUpdateCallback(callback: (queue: QueueItem[], currentIndex: number, isPlaying: boolean) => void): void {
     this.onUIUpdate = callback;
+    this.updateUI();
   }
 
   // 表示設定の読み込み（localStorage）
@@ -119,92 +103,21 @@ export class TextQueue {
     console.log('🔄 表示設定をリセットしました');
   }
 
-  // Slackメッセージをキューに追加（現行実装と同じ）
+  // Slackメッセージをキューに追加（追加したメッセージから即座に表示される）
   addSlackMessage(messageData: SlackMessage): void {
     const hasText = messageData.text && messageData.text.trim();
     const hasImages = messageData.images && messageData.images.length > 0;
     if (hasText || hasImages) {
-      const queueItem: QueueItem = {
-        id: Date.now(),
-        text: hasText ? messageData.text.trim() : '',
-        user: messageData.user,
-        userIcon: messageData.userIcon,
-        timestamp: new Date().toLocaleTimeString(),
-        type: 'slack',
-        replyToUser: messageData.replyToUser,
-        replyToText: messageData.replyToText,
-        images: messageData.images,
-        channel: messageData.channel,
-        slackTs: messageData.timestamp,
-      };
-
-      // キューサイズ制限（TypeScript版で追加）
-      if (this.queue.length >= this.maxQueueSize) {
-        this.queue.shift(); // 古いメッセージを削除
-        if (this.currentIndex > 0) {
-          this.currentIndex--;
-        }
-        console.log(`📦 キューサイズ制限により古いメッセージを削除（最大${this.maxQueueSize}件）`);
-      }
-
-      this.queue.push(queueItem);
-      this.updateUI();
-
-      // 既存タイマーをクリア
-      if (this.currentTimer) {
-        clearTimeout(this.currentTimer);
-        this.currentTimer = null;
-      }
-
-      // 新しいメッセージを即座に表示
-      this.currentIndex = this.queue.length - 1;
-      this.playNext();
-
-      console.log('📝 Slackメッセージをキューに追加:', {
-        id: queueItem.id,
-        text: queueItem.text.substring(0, 50),
-        user: queueItem.user,
-        queueLength: this.queue.length
-      });
+      tauriAPI.queueAddMessage({ ...messageData, text: hasText ? messageData.text.trim() : '' })
+        .catch((error) => console.error('❌ キュー追加エラー:', error));
     }
   }
 
   // プレーンテキストをキューに追加
   addTextMessage(text: string): void {
     if (text && text.trim()) {
-      const queueItem: QueueItem = {
-        id: Date.now(),
-        text: text.trim(),
-        timestamp: new Date().toLocaleTimeString(),
-        type: 'text'
-      };
-
-      // キューサイズ制限
-      if (this.queue.length >= this.maxQueueSize) {
-        this.queue.shift();
-        if (this.currentIndex > 0) {
-          this.currentIndex--;
-        }
-      }
-
-      this.queue.push(queueItem);
-      this.updateUI();
-
-      // 既存タイマーをクリア
-      if (this.currentTimer) {
-        clearTimeout(this.currentTimer);
-        this.currentTimer = null;
-      }
-
-      // 新しいメッセージを即座に表示
-      this.currentIndex = this.queue.length - 1;
-      this.playNext();
-
-      console.log('📝 テキストメッセージをキューに追加:', {
-        id: queueItem.id,
-        text: queueItem.text.substring(0, 50),
-        queueLength: this.queue.length
-      });
+      tauriAPI.queueAddText(text.trim())
+        .catch((error) => console.error('❌ キュー追加エラー:', error));
     }
   }
 
@@ -214,93 +127,16 @@ export class TextQueue {
       console.log('📭 キューが空のため再生できません');
       return;
     }
-
-    if (this.currentIndex === -1) {
-      this.currentIndex = 0;
-    }
-
-    this.isPlaying = true;
-    this.playNext();
-    console.log('▶️ キュー再生開始');
+    tauriAPI.queuePlay().then(() => console.log('▶️ キュー再生開始'));
   }
 
-  // 次のメッセージを再生（現行実装と同じ3秒間隔）
-  private playNext(): void {
-    if (this.currentIndex >= this.queue.length) {
-      // 末尾まで再生したら停止
-      this.isPlaying = false;
-      this.currentTimer = null;
-      this.updateUI();
-      console.log('⏹️ キュー再生完了');
-      return;
-    }
-
-    const currentItem = this.queue[this.currentIndex];
-    
-    if (currentItem.type === 'slack') {
-      // Slackメッセージとして表示
-      this.sendToDisplay(currentItem.text, {
-        user: currentItem.user || 'unknown',
-        userIcon: currentItem.userIcon || '',
-        text: currentItem.text,
-        type: 'slack',
-        replyToUser: currentItem.replyToUser,
-        replyToText: currentItem.replyToText,
-        images: currentItem.images,
-        channel: currentItem.channel,
-        slackTs: currentItem.slackTs,
-      });
-    } else {
-      // プレーンテキストとして表示
-      this.sendToDisplay(currentItem.text);
-    }
-
-    this.updateUI();
-
-    // 3秒後に次のメッセージへ（現行実装と同じ）
-    this.currentTimer = setTimeout(() => {
-      this.currentIndex++;
-      this.playNext();
-    }, 3000);
-
-    console.log('📺 メッセージ表示:', {
-      index: this.currentIndex + 1,
-      total: this.queue.length,
-      type: currentItem.type,
-      text: currentItem.text.substring(0, 50)
-    });
+  // 指定位置から再生
+  select(index: number): void {
+    tauriAPI.queueSelect(index);
   }
 
-  // 表示ウィンドウにメッセージ送信（IPC経由）
-  private sendToDisplay(text: string, metadata?: any): void {
-    try {
-      if (this.onMessageSend) {
-        if (metadata) {
-          // Slackメッセージとして送信
-          this.onMessageSend({
-            text: text,
-            user: metadata.user,
-            userIcon: metadata.userIcon,
-            replyToUser: metadata.replyToUser,
-            replyToText: metadata.replyToText,
-            images: metadata.images,
-            channel: metadata.channel,
-            timestamp: metadata.slackTs,
-          });
-        } else {
-          // プレーンテキストとして送信
-          this.onMessageSend({
-            text: text,
-            user: 'System',
-            userIcon: ''
-          });
-        }
-      } else {
-        console.warn('⚠️ メッセージ送信コールバックが設定されていません');
-      }
-    } catch (error) {
-      console.error('❌ メッセージ送信エラー:', error);
-    }
+  remove(id: number): void {
+    tauriAPI.queueRemove(id);
   }
 
   // UI更新
@@ -310,41 +146,23 @@ export class TextQueue {
     }
   }
 
-  // キュー制御メソッド（TypeScript版で追加）
+  // キュー制御メソッド
   pause(): void {
-    if (this.currentTimer) {
-      clearTimeout(this.currentTimer);
-      this.currentTimer = null;
-    }
-    this.isPlaying = false;
-    this.updateUI();
-    console.log('⏸️ キュー一時停止');
+    tauriAPI.queuePause().then(() => console.log('⏸️ キュー一時停止'));
   }
 
   resume(): void {
-    if (!this.isPlaying && this.currentIndex < this.queue.length) {
-      this.isPlaying = true;
-      this.playNext();
-      console.log('▶️ キュー再開');
-    }
+    tauriAPI.queueResume().then((resumed) => {
+      if (resumed) console.log('▶️ キュー再開');
+    });
   }
 
   stop(): void {
-    if (this.currentTimer) {
-      clearTimeout(this.currentTimer);
-      this.currentTimer = null;
-    }
-    this.isPlaying = false;
-    this.currentIndex = -1;
-    this.updateUI();
-    console.log('⏹️ キュー停止');
+    tauriAPI.queueStop().then(() => console.log('⏹️ キュー停止'));
   }
 
   clear(): void {
-    this.stop();
-    this.queue = [];
-    this.updateUI();
-    console.log('🗑️ キューをクリア');
+    tauriAPI.queueClear().then(() => console.log('🗑️ キューをクリア'));
   }
 
   // 設定取得・更新
@@ -360,11 +178,11 @@ export class TextQueue {
   }
 
   // 状態取得
-  getStatus(): { 
-    queueLength: number; 
-    currentIndex: number; 
-    isPlaying: boolean; 
-    queue: QueueItem[] 
+  getStatus(): {
+    queueLength: number;
+    currentIndex: number;
+    isPlaying: boolean;
+    queue: QueueItem[]
   } {
     return {
       queueLength: this.queue.length,
@@ -376,4 +194,4 @@ export class TextQueue {
 }
 
 // シングルトンインスタンス
-export const textQueue = new TextQueue();
\ No newline at end of file
+export const textQueue = new TextQueue();
@@ -3,7 +3,13 @@ import { listen, emit } from '@tauri-apps/api/event';
 import {
   SlackConfig, SlackConnectionResult, ConfigSaveResult, ConfigLoadResult,
   SlackMessage, ChannelListResult, ChannelActionResult, SlackChannel,
-  EmojiListResult, SlackReactionEvent, DisplayMessageImagesUpdate
+  EmojiListResult, SlackReactionEvent, DisplayMessageImagesUpdate, ThreadResult,
+  WatchedThread, ChannelRule, ChannelRuleApplyResult, ChannelValidationResult,
+  ChannelSettings, MessageReactions, FilterRule, FilterTestResult, FilterStats,
+  HighlightRule, RedactionSettings, NgWordEntry, NgWordFormat, ThrottleMetrics,
+  PauseState, ResumeMode, ScheduleSettings, ScheduleState, MessageQueueState,
+  QueuePosition, HistoryQuery, HistoryEntry, HistorySettings, HistoryExportRequest,
+  RecorderStatus, ReplayState
 } from './types';
 
 /**
@@ -60,11 +66,19 @@ export const tauriAPI = {
     }).then(fn => { if (cancelled) { fn(); } else { unlisten = fn; } });
     return () => { cancelled = true; if (unlisten) unlisten(); };
   },
+  onSlackReactionsUpdated: (callback: (event: MessageReactions) => void): (() => void) => {
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+    listen<MessageReactions>('slack-reactions-updated', (event) => {
+      callback(event.payload);
+    }).then(fn => { if (cancelled) { fn(); } else { unlisten = fn; } });
+    return () => { cancelled = true; if (unlisten) unlisten(); };
+  },
   // チャンネル管理
   slackGetChannels: (): Promise<ChannelListResult> =>
     invoke('slack_get_channels'),
-  addWatchChannel: (channelId: string): Promise<ChannelActionResult> =>
-    invoke('slack_add_channel', { channelId }),
+  addWatchChannel: (channelId: string, autoJoin: boolean = false): Promise<ChannelActionResult> =>
+    invoke('slack_add_channel', { channelId, autoJoin }),
   removeWatchChannel: (channelId: string): Promise<ChannelActionResult> =>
     invoke('slack_remove_channel', { channelId }),
   getChannelInfo: (channelId: string): Promise<SlackChannel> =>
@@ -81,10 +95,166 @@ export const tauriAPI = {
     }).then(fn => { if (cancelled) { fn(); } else { unlisten = fn; } });
     return () => { cancelled = true; if (unlisten) unlisten(); };
   },
+  addChannelRule: (rule: ChannelRule): Promise<ChannelActionResult> =>
+    invoke('slack_add_channel_rule', { rule }),
+  removeChannelRule: (pattern: string): Promise<ChannelActionResult> =>
+    invoke('slack_remove_channel_rule', { pattern }),
+  getChannelRules: (): Promise<ChannelRule[]> =>
+    invoke('slack_get_channel_rules'),
+  applyChannelRules: (): Promise<ChannelRuleApplyResult> =>
+    invoke('slack_apply_channel_rules'),
+  validateChannels: (): Promise<ChannelValidationResult> =>
+    invoke('slack_validate_channels'),
+  getChannelSettings: (channelId: string): Promise<ChannelSettings> =>
+    invoke('slack_get_channel_settings', { channelId }),
+  setChannelSettings: (channelId: string, settings: ChannelSettings): Promise<ChannelActionResult> =>
+    invoke('slack_set_channel_settings', { channelId, settings }),
+
+  // メッセージフィルタ
+  addFilterRule: (rule: FilterRule): Promise<ChannelActionResult> =>
+    invoke('slack_add_filter_rule', { rule }),
+  removeFilterRule: (id: string): Promise<ChannelActionResult> =>
+    invoke('slack_remove_filter_rule', { id }),
+  getFilterRules: (): Promise<FilterRule[]> =>
+    invoke('slack_get_filter_rules'),
+  testFilterRule: (rule: FilterRule, text: string, userId?: string): Promise<FilterTestResult> =>
+    invoke('slack_test_filter_rule', { rule, text, userId }),
+  getFilterStats: (): Promise<FilterStats> =>
+    invoke('slack_get_filter_stats'),
+
+  // ハイライト
+  addHighlightRule: (rule: HighlightRule): Promise<ChannelActionResult> =>
+    invoke('slack_add_highlight_rule', { rule }),
+  removeHighlightRule: (id: string): Promise<ChannelActionResult> =>
+    invoke('slack_remove_highlight_rule', { id }),
+  getHighlightRules: (): Promise<HighlightRule[]> =>
+    invoke('slack_get_highlight_rules'),
+
+  // マスク
+  getRedactionSettings: (): Promise<RedactionSettings> =>
+    invoke('slack_get_redaction_settings'),
+  setRedactionSettings: (settings: RedactionSettings): Promise<ChannelActionResult> =>
+    invoke('slack_set_redaction_settings', { settings }),
+
+  // 一時停止
+  getPauseState: (): Promise<PauseState> =>
+    invoke('slack_get_pause_state'),
+  pauseStream: (): Promise<PauseState> =>
+    invoke('slack_pause_stream'),
+  resumeStream: (mode: ResumeMode): Promise<PauseState> =>
+    invoke('slack_resume_stream', { mode }),
+
+  // スケジュール
+  getSchedule: (): Promise<ScheduleSettings> =>
+    invoke('slack_get_schedule'),
+  setSchedule: (settings: ScheduleSettings): Promise<ChannelActionResult> =>
+    invoke('slack_set_schedule', { settings }),
+  getScheduleState: (): Promise<ScheduleState> =>
+    invoke('slack_get_schedule_state'),
+
+  // 受信イベントの記録
+  getEventRecording: (): Promise<RecorderStatus> =>
+    invoke('slack_get_event_recording'),
+  setEventRecording: (enabled: boolean): Promise<RecorderStatus> =>
+    invoke('slack_set_event_recording', { enabled }),
+
+  // リプレイ（speed は 1 で記録時と同じ間隔）
+  replayGetState: (): Promise<ReplayState> =>
+    invoke('replay_get_state'),
+  replayStart: (path: string, speed: number): Promise<ReplayState> =>
+    invoke('replay_start', { path, speed }),
+  replayStop: (): Promise<ReplayState> =>
+    invoke('replay_stop'),
+
+  // メッセージキュー
+  queueGetState: (): Promise<MessageQueueState> =>
+    invoke('queue_get_state'),
+  queueAddMessage: (message: SlackMessage): Promise<number> =>
+    invoke('queue_add_message', { message }),
+  queueAddText: (text: string): Promise<number> =>
+    invoke('queue_add_text', { text }),
+  queueRemove: (id: number): Promise<boolean> =>
+    invoke('queue_remove', { id }),
+  queuePlay: (): Promise<boolean> =>
+    invoke('queue_play'),
+  queueSelect: (index: number): Promise<boolean> =>
+    invoke('queue_select', { index }),
+  queuePause: (): Promise<void> =>
+    invoke('queue_pause'),
+  queueResume: (): Promise<boolean> =>
+    invoke('queue_resume'),
+  queueStop: (): Promise<void> =>
+    invoke('queue_stop'),
+  queueClear: (): Promise<void> =>
+    invoke('queue_clear'),
+  onQueueUpdated: (callback: (queue: MessageQueueState) => void): (() => void) => {
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+    listen<MessageQueueState>('queue-updated', (event) => {
+      callback(event.payload);
+    }).then(fn => { if (cancelled) { fn(); } else { unlisten = fn; } });
+    return () => { cancelled = true; if (unlisten) unlisten(); };
+  },
+  onQueuePosition: (callback: (position: QueuePosition) => void): (() => void) => {
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+    listen<QueuePosition>('queue-position', (event) => {
+      callback(event.payload);
+    }).then(fn => { if (cancelled) { fn(); } else { unlisten = fn; } });
+    return () => { cancelled = true; if (unlisten) unlisten(); };
+  },
+
+  // メッセージ履歴
+  searchHistory: (query: HistoryQuery): Promise<HistoryEntry[]> =>
+    invoke('search_history', { query }),
+  getHistorySettings: (): Promise<HistorySettings> =>
+    invoke('get_history_settings'),
+  setHistorySettings: (settings: HistorySettings): Promise<ChannelActionResult> =>
+    invoke('set_history_settings', { settings }),
+  exportHistory: (request: HistoryExportRequest): Promise<ChannelActionResult> =>
+    invoke('export_history', { request }),
+
+  // 流量制限
+  getThrottleMetrics: (): Promise<ThrottleMetrics> =>
+    invoke('slack_get_throttle_metrics'),
+
+  // 匿名表示
+  getAnonymousMode: (): Promise<boolean> =>
+    invoke('slack_get_anonymous_mode'),
+  setAnonymousMode: (enabled: boolean): Promise<ChannelActionResult> =>
+    invoke('slack_set_anonymous_mode', { enabled }),
+
+  // NGワード
+  getNgWords: (): Promise<NgWordEntry[]> =>
+    invoke('slack_get_ng_words'),
+  addNgWord: (word: string, hideMessage: boolean): Promise<ChannelActionResult> =>
+    invoke('slack_add_ng_word', { word, hideMessage }),
+  removeNgWord: (word: string): Promise<ChannelActionResult> =>
+    invoke('slack_remove_ng_word', { word }),
+  importNgWords: (content: string, format: NgWordFormat, replace: boolean): Promise<ChannelActionResult> =>
+    invoke('slack_import_ng_words', { content, format, replace }),
+  exportNgWords: (format: NgWordFormat): Promise<string> =>
+    invoke('slack_export_ng_words', { format }),
+
+  // スレッド
+  getThread: (channelId: string, threadTs: string): Promise<ThreadResult> =>
+    invoke('slack_get_thread', { channelId, threadTs }),
+  watchThread: (channelId: string, threadTs: string): Promise<ChannelActionResult> =>
+    invoke('slack_watch_thread', { channelId, threadTs }),
+  unwatchThread: (channelId: string, threadTs: string): Promise<ChannelActionResult> =>
+    invoke('slack_unwatch_thread', { channelId, threadTs }),
+  getWatchedThreads: (): Promise<WatchedThread[]> =>
+    invoke('slack_get_watched_threads'),
 
   // ユーザー管理
   slackReloadUsers: (): Promise<{ success: boolean, count?: number, error?: string }> =>
     invoke('slack_reload_users'),
+  followUser: (userId: string): Promise<ChannelActionResult> =>
+    invoke('slack_follow_user', { userId }),
+  unfollowUser: (userId: string): Promise<ChannelActionResult> =>
+    invoke('slack_unfollow_user', { userId }),
+  getFollowedUsers: (): Promise<string[]> =>
+    invoke('slack_get_followed_users'),
   slackGetUsersCount: (): Promise<{ success: boolean, count: number, error?: string }> =>
     invoke('get_users_count'),
   onUserDataUpdated: (callback: (count: number) => void): (() => void) => {
@@ -3,6 +3,167 @@ export interface SlackConfig {
   appToken: string;
   channels?: string[];                    // 監視チャンネルID一覧
   watchedChannelData?: { [key: string]: SlackChannel }; // チャンネル詳細情報
+  threadParentMaxChars?: number;          // スレッド親メッセージの最大文字数（0で省略しない）
+  watchedThreads?: WatchedThread[];       // 個別に監視するスレッド
+  followedUsers?: string[];               // チャンネルを問わず表示するユーザーID
+  channelRules?: ChannelRule[];           // パターン一致で自動監視するチャンネルルール
+  channelSettings?: { [channelId: string]: ChannelSettings }; // チャンネルごとの設定
+  trendingThreshold?: number;             // リアクション合計がこの数に達したら再表示（0で無効）
+  filterRules?: FilterRule[];             // 表示前に適用するメッセージフィルタ
+  highlightRules?: HighlightRule[];       // 一致箇所を強調するルール
+  redaction?: RedactionSettings;          // 表示前のマスク設定（チャンネル設定で上書き可）
+  anonymousMode?: boolean;                // 発言者名・アイコン・メンションを仮名で表示
+  maxMessagesPerMinute?: number;          // チャンネルごとの1分あたりの表示上限（超過分はダイジェスト。0で無制限）
+  schedule?: ScheduleSettings;            // 勤務時間外・会議中などに表示を止めるスケジュール
+  history?: HistorySettings;              // 受信したメッセージの履歴の保存設定
+}
+
+export interface RedactionSettings {
+  enabled: boolean;
+  emails?: boolean;
+  phones?: boolean;
+  tokens?: boolean;             // Slack / AWS / GitHub のトークン
+  creditCards?: boolean;
+  customPatterns?: string[];    // 追加でマスクする正規表現
+}
+
+export interface NgWordEntry {
+  word: string;
+  hideMessage?: boolean;        // 伏せ字にせずメッセージ全体を表示しない
+}
+
+export type NgWordFormat = 'text' | 'csv';
+
+export interface HighlightRule {
+  id?: string;                  // 省略時はバックエンドで採番
+  channel?: string;             // 対象チャンネルID（省略で全チャンネル）
+  kind?: 'keyword' | 'regex';
+  pattern: string;
+  color?: string;               // CSSの色指定
+  priority?: number;            // 範囲が重なった場合は大きい方を優先
+}
+
+export interface Highlight {
+  ruleId: string;
+  start: number;                // text 中の位置（UTF-16）
+  end: number;
+  color?: string;
+  priority: number;
+}
+
+export interface FilterRule {
+  id?: string;                  // 省略時はバックエンドで採番
+  channel?: string;             // 対象チャンネルID（省略で全チャンネル）
+  action?: 'exclude' | 'include';
+  kind?: 'keyword' | 'regex' | 'user';
+  pattern: string;              // キーワード・正規表現・ユーザーID
+}
+
+export interface FilterTestResult {
+  success: boolean;
+  matched: boolean;
+  error?: string;
+}
+
+export interface FilterStats {
+  filtered_count: number;
+  by_rule: { [ruleId: string]: number };
+}
+
+export interface PauseState {
+  paused: boolean;
+  scheduled: boolean;           // スケジュールによる一時停止を含む
+  buffered: number;             // 一時停止中に溜めているメッセージ数
+  dropped: number;              // 上限を超えて捨てたメッセージ数
+}
+
+export type ResumeMode = 'all' | 'summary' | 'discard';
+
+export interface WeeklyRange {
+  weekdays?: number[];          // 1=月曜 … 7=日曜（省略で毎日）
+  start: string;                // "HH:MM"
+  end: string;                  // start より前なら日付をまたぐ
+}
+
+export interface ScheduleExclusion {
+  start: string;                // "YYYY-MM-DDTHH:MM"（この期間はスケジュールを適用しない）
+  end: string;
+  label?: string;
+}
+
+export type QuietAction = 'pause' | 'mentionsOnly';
+
+export interface ScheduleSettings {
+  enabled: boolean;
+  workingHours?: WeeklyRange[];  // 表示する時間帯（空なら終日）
+  quietHours?: WeeklyRange[];    // 定例会議など表示しない時間帯
+  holidays?: string[];           // "YYYY-MM-DD"
+  exclusions?: ScheduleExclusion[];
+  quietAction?: QuietAction;
+  resumeMode?: ResumeMode;       // 一時停止から戻るときの扱い
+}
+
+// 受信イベントの記録（調査用）
+export interface RecorderStatus {
+  enabled: boolean;
+  path?: string;                // 記録中のファイル
+}
+
+// 記録したイベントのリプレイ
+export interface ReplayState {
+  running: boolean;
+  processed: number;            // 処理したイベント数
+  total: number;
+}
+
+export interface ScheduleState {
+  quiet: boolean;
+  action?: QuietAction;
+  reason?: string;
+}
+
+export interface ThrottleMetrics {
+  collapsedCount: number;       // ダイジェストにまとめたメッセージ数
+  digestCount: number;
+  byChannel: { [channelId: string]: number };
+}
+
+export interface ChannelSettings {
+  announceMembership?: boolean; // メンバーの参加・退出を表示
+  redaction?: RedactionSettings; // このチャンネルだけマスク設定を変える
+  maxMessagesPerMinute?: number; // このチャンネルだけ1分あたりの表示上限を変える
+}
+
+export interface ChannelRule {
+  pattern: string;              // 例: "times-*"
+  kind?: 'glob' | 'regex';
+  autoJoin?: boolean;           // 未参加のパブリックチャンネルに自動参加
+}
+
+export interface ChannelRuleApplyResult {
+  success: boolean;
+  added: SlackChannel[];
+  error?: string;
+}
+
+export type ChannelHealthStatus = 'ok' | 'not_member' | 'archived' | 'not_found' | 'unknown';
+
+export interface ChannelHealth {
+  channel_id: string;
+  name: string;
+  status: ChannelHealthStatus;
+  error?: string;
+}
+
+export interface ChannelValidationResult {
+  success: boolean;
+  channels: ChannelHealth[];
+  error?: string;
+}
+
+export interface WatchedThread {
+  channel: string;
+  threadTs: string;
 }
 
 export interface SlackConnectionResult {
@@ -29,33 +190,107 @@ export interface ImageData {
 export interface SlackMessage {
   text: string;
   user: string;
+  userId?: string;
   userIcon: string;
   channel?: string;
   timestamp?: string;
-  _queueAction?: 'addToQueue'; // TextQueue操作用の内部フラグ
+  _queueAction?: 'addToQueue' | 'addSystemMessage'; // TextQueue操作用の内部フラグ
   threadTs?: string;
   replyToUser?: string;
   replyToText?: string;
+  replyToUserIcon?: string;
+  replyToImages?: ImageData[];
   images?: ImageData[];
+  channelName?: string; // 発言元チャンネル名
+  channelType?: 'channel' | 'group' | 'im' | 'mpim';
+  isSystem?: boolean;   // 参加・退出などのシステムメッセージ
+  trending?: boolean;   // リアクションが閾値を超えて再表示
+  highlights?: Highlight[];
+  redacted?: boolean;   // メールアドレス・トークン等をマスク済み
+  attachments?: string[]; // リンクのプレビューや連携アプリの投稿の本文
+}
+
+// メッセージ履歴
+export interface HistorySettings {
+  enabled: boolean;
+  retentionDays: number;        // 保持する日数（0で無期限）
+  maxMessages: number;          // 保持する最大件数（0で無制限）
+}
+
+export interface HistoryQuery {
+  query?: string;               // 空白区切りの語をすべて含むメッセージ
+  channels?: string[];
+  userIds?: string[];
+  from?: string;                // YYYY-MM-DD（ローカル日付、両端を含む）
+  to?: string;
+  limit?: number;
+  includeHidden?: boolean;      // フィルタ等で表示しなかったメッセージも含める
+}
+
+export interface HistoryEntry {
+  id: number;
+  channel?: string;
+  channelName?: string;
+  ts?: string;
+  threadTs?: string;
+  userId?: string;
+  user: string;
+  text: string;                 // メンションを実名で解決したプレーンテキスト
+  reactions: ReactionData[];
+  isSystem: boolean;
+  hiddenReason?: 'filter' | 'ng_word' | 'schedule' | 'throttled'; // 表示しなかった理由
+  redacted: boolean;            // 表示時にマスクした
+  masked: boolean;              // 表示時にNGワードを伏せ字にした
+  postedAt: number;             // Unix 秒
+}
+
+export type HistoryExportFormat = 'jsonl' | 'csv' | 'markdown';
+
+export interface HistoryExportRequest {
+  channels?: string[];          // 空ならすべてのチャンネル
+  from?: string;                // YYYY-MM-DD
+  to?: string;
+  format: HistoryExportFormat;  // csv は Excel 向けに BOM 付き UTF-8
+  path: string;                 // 書き出し先のファイル
+}
+
+// バックエンドのメッセージキュー
+export interface QueueItem extends SlackMessage {
+  id: number;
+  reactions?: ReactionData[];
+  addedAt: number;              // キューに追加した時刻（Unix ミリ秒）
+}
+
+export interface QueuePosition {
+  currentIndex: number | null;  // items.length と等しい場合は末尾まで再生済み
+  isPlaying: boolean;
+}
+
+export interface MessageQueueState extends QueuePosition {
+  items: QueueItem[];
 }
 
 export interface MessageImagesReady {
   channel: string;
   timestamp: string;
   images: ImageData[];
+  replyTo?: boolean; // trueの場合はスレッド親メッセージの画像
 }
 
 export interface DisplayMessageImagesUpdate {
   channel: string;
   timestamp: string;
   images: ImageData[];
+  replyTo?: boolean;
 }
 
 export interface SlackChannel {
   id: string;
-  name: string;
+  name: string;           // DMは相手ユーザー名、グループDMは参加者名
   is_private?: boolean;
   is_member?: boolean;
+  channel_type?: 'im' | 'mpim';
+  is_archived?: boolean;
 }
 
 export interface ChannelListResult {
@@ -70,6 +305,12 @@ export interface ChannelActionResult {
   message?: string;
 }
 
+export interface ThreadResult {
+  success: boolean;
+  messages?: SlackMessage[];
+  error?: string;
+}
+
 export interface CustomEmoji {
   name: string;
   url: string;
@@ -84,7 +325,13 @@ export interface EmojiListResult {
 export interface ReactionData {
   name: string;
   count: number;
-  users: string[];
+  users: string[];  // リアクションしたユーザーID
+}
+
+export interface MessageReactions {
+  channel: string;
+  message_ts: string;
+  reactions: ReactionData[];
 }
 
 export interface SlackReactionEvent {
@@ -108,17 +355,91 @@ export interface TauriAPI {
   onDisplaySlackMessage: (callback: (message: SlackMessage) => void) => () => void;
   onAddToTextQueue: (callback: (message: SlackMessage) => void) => () => void;
   onSlackReaction: (callback: (event: SlackReactionEvent) => void) => () => void;
+  onSlackReactionsUpdated: (callback: (event: MessageReactions) => void) => () => void;
   // チャンネル管理
   slackGetChannels: () => Promise<ChannelListResult>;
-  addWatchChannel: (channelId: string) => Promise<ChannelActionResult>;
+  addWatchChannel: (channelId: string, autoJoin?: boolean) => Promise<ChannelActionResult>;
   removeWatchChannel: (channelId: string) => Promise<ChannelActionResult>;
   getChannelInfo: (channelId: string) => Promise<SlackChannel>;
   getWatchedChannels: () => Promise<{ ids: string[], data: { [key: string]: SlackChannel } }>;
   getCurrentChannelName: () => Promise<string>;
   onChannelUpdated: (callback: (channelName: string) => void) => () => void;
+  addChannelRule: (rule: ChannelRule) => Promise<ChannelActionResult>;
+  removeChannelRule: (pattern: string) => Promise<ChannelActionResult>;
+  getChannelRules: () => Promise<ChannelRule[]>;
+  applyChannelRules: () => Promise<ChannelRuleApplyResult>;
+  validateChannels: () => Promise<ChannelValidationResult>;
+  getChannelSettings: (channelId: string) => Promise<ChannelSettings>;
+  setChannelSettings: (channelId: string, settings: ChannelSettings) => Promise<ChannelActionResult>;
+  // メッセージフィルタ
+  addFilterRule: (rule: FilterRule) => Promise<ChannelActionResult>;
+  removeFilterRule: (id: string) => Promise<ChannelActionResult>;
+  getFilterRules: () => Promise<FilterRule[]>;
+  testFilterRule: (rule: FilterRule, text: string, userId?: string) => Promise<FilterTestResult>;
+  getFilterStats: () => Promise<FilterStats>;
+  // ハイライト
+  addHighlightRule: (rule: HighlightRule) => Promise<ChannelActionResult>;
+  removeHighlightRule: (id: string) => Promise<ChannelActionResult>;
+  getHighlightRules: () => Promise<HighlightRule[]>;
+  // マスク
+  getRedactionSettings: () => Promise<RedactionSettings>;
+  setRedactionSettings: (settings: RedactionSettings) => Promise<ChannelActionResult>;
+  // 一時停止
+  getPauseState: () => Promise<PauseState>;
+  pauseStream: () => Promise<PauseState>;
+  resumeStream: (mode: ResumeMode) => Promise<PauseState>;
+  // スケジュール
+  getSchedule: () => Promise<ScheduleSettings>;
+  setSchedule: (settings: ScheduleSettings) => Promise<ChannelActionResult>;
+  getScheduleState: () => Promise<ScheduleState>;
+  // 受信イベントの記録
+  getEventRecording: () => Promise<RecorderStatus>;
+  setEventRecording: (enabled: boolean) => Promise<RecorderStatus>;
+  // リプレイ
+  replayGetState: () => Promise<ReplayState>;
+  replayStart: (path: string, speed: number) => Promise<ReplayState>;
+  replayStop: () => Promise<ReplayState>;
+  // メッセージキュー
+  queueGetState: () => Promise<MessageQueueState>;
+  queueAddMessage: (message: SlackMessage) => Promise<number>;
+  queueAddText: (text: string) => Promise<number>;
+  queueRemove: (id: number) => Promise<boolean>;
+  queuePlay: () => Promise<boolean>;
+  queueSelect: (index: number) => Promise<boolean>;
+  queuePause: () => Promise<void>;
+  queueResume: () => Promise<boolean>;
+  queueStop: () => Promise<void>;
+  queueClear: () => Promise<void>;
+  onQueueUpdated: (callback: (queue: MessageQueueState) => void) => () => void;
+  onQueuePosition: (callback: (position: QueuePosition) => void) => () => void;
+  // メッセージ履歴
+  searchHistory: (query: HistoryQuery) => Promise<HistoryEntry[]>;
+  getHistorySettings: () => Promise<HistorySettings>;
+  setHistorySettings: (settings: HistorySettings) => Promise<ChannelActionResult>;
+  exportHistory: (request: HistoryExportRequest) => Promise<ChannelActionResult>;
+  // 流量制限
+  getThrottleMetrics: () => Promise<ThrottleMetrics>;
+  // 匿名表示
+  getAnonymousMode: () => Promise<boolean>;
+  setAnonymousMode: (enabled: boolean) => Promise<ChannelActionResult>;
+  // NGワード
+  getNgWords: () => Promise<NgWordEntry[]>;
+  addNgWord: (word: string, hideMessage: boolean) => Promise<ChannelActionResult>;
+  removeNgWord: (word: string) => Promise<ChannelActionResult>;
+  importNgWords: (content: string, format: NgWordFormat, replace: boolean) => Promise<ChannelActionResult>;
+  exportNgWords: (format: NgWordFormat) => Promise<string>;
+
+  // スレッド
+  getThread: (channelId: string, threadTs: string) => Promise<ThreadResult>;
+  watchThread: (channelId: string, threadTs: string) => Promise<ChannelActionResult>;
+  unwatchThread: (channelId: string, threadTs: string) => Promise<ChannelActionResult>;
+  getWatchedThreads: () => Promise<WatchedThread[]>;
 
   // ユーザー管理
   slackReloadUsers: () => Promise<{ success: boolean, count?: number, error?: string }>;
+  followUser: (userId: string) => Promise<ChannelActionResult>;
+  unfollowUser: (userId: string) => Promise<ChannelActionResult>;
+  getFollowedUsers: () => Promise<string[]>;
   slackGetUsersCount: () => Promise<{ success: boolean, count: number, error?: string }>;
   onUserDataUpdated: (callback: (count: number) => void) => () => void;
 